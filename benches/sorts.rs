@@ -0,0 +1,109 @@
+//! Criterion benchmarks of the sorts over the input sequences of
+//! [`sort::generators`], at several sizes, run with `cargo bench`.  Slower
+//! but steadier than the binary's harness: each measurement is warmed up and
+//! sampled repeatedly, and reported with its confidence interval and outliers.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use sort::{generators::*, *};
+
+/// Seed of the random input sequences, the same as the binary's.
+const SEED: u64 = 42;
+
+/// Sizes of the inputs of the $O(n^2)$ sorts.
+const QUADRATIC_SIZES: [usize; 2] = [100, 1_000];
+/// Sizes of the inputs of the $O(n \log n)$ sorts, and the linear ones.
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Input sequences of `n` elements, by name.
+fn inputs(n: usize) -> [(&'static str, Vec<usize>); 7] {
+    [
+        ("random", random_sequence(n, SEED)),
+        ("increasing", increasing_sequence(n)),
+        ("decreasing", decreasing_sequence(n)),
+        ("nearly_sorted", nearly_sorted_sequence(n, n / 200, SEED)),
+        ("few_unique", few_unique_sequence(n, 16, SEED)),
+        ("pipe_organ", pipe_organ_sequence(n)),
+        ("sawtooth", sawtooth_sequence(n, 1000)),
+    ]
+}
+
+/// Benchmark each of the `sorts`, in a group named `group`, over every input
+/// sequence of each of the `sizes`.  The input is copied outside of the timed
+/// section, so only the sort is measured.
+macro_rules! bench_sorts {
+    ($c:expr, $group:expr, $sizes:expr, $($sort:expr),+ $(,)?) => {{
+        let mut group = $c.benchmark_group($group);
+        for n in $sizes {
+            for (input_name, input) in inputs(n) {
+                $(
+                    group.bench_with_input(
+                        BenchmarkId::new(format!("{}/{input_name}", stringify!($sort)), n),
+                        &input,
+                        |b, input| {
+                            b.iter_batched_ref(|| input.clone(), |v| $sort(v), BatchSize::LargeInput)
+                        },
+                    );
+                )+
+            }
+        }
+        group.finish();
+    }};
+}
+
+fn quadratic(c: &mut Criterion) {
+    bench_sorts!(
+        c,
+        "quadratic",
+        QUADRATIC_SIZES,
+        gnome_sort,
+        bubble_sort,
+        cocktail_sort,
+        odd_even_sort,
+        selection_sort,
+        pancake_sort,
+        insertion_sort,
+    );
+}
+
+fn n_log_n(c: &mut Criterion) {
+    bench_sorts!(
+        c,
+        "n_log_n",
+        SIZES,
+        comb_sort,
+        shell_sort,
+        heap_sort,
+        heap_sort_topdown_build,
+        smooth_sort,
+        quick_sort,
+        quick_sort_3,
+        quick_sort_median_of_3,
+        quick_sort_median_of_medians,
+        quick_sort_hinted,
+        intro_sort,
+        merge_sort_top_down,
+        merge_sort_top_down_insert,
+        merge_sort_top_down_adaptive,
+        merge_sort_copy,
+        merge_sort_bottom_up,
+        merge_sort_bottom_up_insert,
+        merge_sort_blocked,
+        merge_sort_in_place,
+        merge_sort_owned,
+        tim_sort,
+        sample_sort,
+        native_sort,
+        native_unstable_sort,
+    );
+}
+
+fn linear(c: &mut Criterion) {
+    bench_sorts!(c, "linear", SIZES, radix_sort);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = quadratic, n_log_n, linear
+}
+criterion_main!(benches);
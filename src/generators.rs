@@ -0,0 +1,111 @@
+//! Input sequences for benchmarks and tests, the random ones drawn from a
+//! generator seeded with `seed`, so the same seed always gives the same input.
+
+use alloc::{vec, vec::Vec};
+use core::iter::repeat_with;
+
+use fastrand::Rng;
+
+/// Return a sequence of `n` random `usize` values, below 10000 so that larger
+/// sequences have repeated values.
+pub fn random_sequence(n: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::with_seed(seed);
+    repeat_with(|| rng.usize(..10000)).take(n).collect()
+}
+
+/// Return a sequence of `n` increasing `usize` values (i.e., `v[i] == i`).
+pub fn increasing_sequence(n: usize) -> Vec<usize> {
+    (0..n).collect()
+}
+
+/// Return a sequence of `n` decreasing `usize` values.
+/// The reverse of [`increasing_sequence`].
+pub fn decreasing_sequence(n: usize) -> Vec<usize> {
+    (0..n).rev().collect()
+}
+
+/// Return a sequence of `n` equal `usize` values.
+pub fn equal_sequence(n: usize) -> Vec<usize> {
+    vec![42; n]
+}
+
+/// Return a sequence of `n - 1` equal values plus a smaller one.
+pub fn last_out_of_order(n: usize) -> Vec<usize> {
+    let mut v = vec![42; n];
+    if let Some(last) = v.last_mut() {
+        *last = 41;
+    }
+    v
+}
+
+/// Return a sequence of a large value followed by `n - 1` equal ones smaller than it.
+pub fn first_out_of_order(n: usize) -> Vec<usize> {
+    let mut v = vec![42; n];
+    if let Some(first) = v.first_mut() {
+        *first = 43;
+    }
+    v
+}
+
+/// Return a sequence of `n` increasing values with `swaps` random pairs
+/// swapped, so at most `2 * swaps` elements are out of place.
+pub fn nearly_sorted_sequence(n: usize, swaps: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::with_seed(seed);
+    let mut v = increasing_sequence(n);
+    if n > 1 {
+        for _ in 0..swaps {
+            v.swap(rng.usize(..n), rng.usize(..n));
+        }
+    }
+    v
+}
+
+/// Return a sequence of `n` random values out of only `unique` distinct ones,
+/// `0..unique`, each repeated many times.
+///
+/// Panics if `unique` is `0` and `n` is not.
+pub fn few_unique_sequence(n: usize, unique: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::with_seed(seed);
+    repeat_with(|| rng.usize(..unique)).take(n).collect()
+}
+
+/// Return a sequence of `n` random values below `n`, skewed towards the small
+/// ones: the cube of a uniform fraction of `n`, so half of them are below
+/// `n / 8` and the smallest values repeat many times.
+pub fn skewed_sequence(n: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::with_seed(seed);
+    repeat_with(|| {
+        let x = rng.f64();
+        (x * x * x * n as f64) as usize
+    })
+    .take(n)
+    .collect()
+}
+
+/// Return a sequence of `n` values increasing up to the middle, then decreasing.
+pub fn pipe_organ_sequence(n: usize) -> Vec<usize> {
+    (0..n).map(|i| i.min(n - 1 - i)).collect()
+}
+
+/// Return a sequence of `n` values increasing in runs of `period`, then
+/// starting over from `0`.
+///
+/// Panics if `period` is `0` and `n` is not.
+pub fn sawtooth_sequence(n: usize, period: usize) -> Vec<usize> {
+    (0..n).map(|i| i % period).collect()
+}
+
+/// Return `n` random words, of 1 to 12 lowercase letters drawn with roughly
+/// the letter frequencies of English, standing in for a corpus of words.
+pub fn random_words(n: usize, seed: u64) -> Vec<Vec<u8>> {
+    const LETTERS: &[u8] = b"eeeeeeeeeeeettttttttaaaaaaaaoooooooiiiiiiinnnnnnnsssssshhhhhhrrrrrrddddllllccuummwwffggyyppbbvkjxqz";
+    let mut rng = Rng::with_seed(seed);
+    repeat_with(|| {
+        let len = rng.usize(1..=12);
+        repeat_with(|| LETTERS[rng.usize(..LETTERS.len())])
+            .take(len)
+            .collect()
+    })
+    .take(n)
+    .collect()
+}
@@ -1,30 +1,82 @@
-/// **Gnome sort** is an insertion sort variant that has no inner loop.
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::BinaryHeap, vec, vec::Vec};
+use core::{
+    cell::Cell,
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    mem,
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+#[cfg(feature = "alloc")]
+use core::{cell::RefCell, cmp::Reverse, ops::Range};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    format,
+    fs::{self, File, OpenOptions},
+    hash::Hash,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    process,
+};
+
+#[cfg(feature = "testutil")]
+pub mod generators;
+
+/// **Gnome sort** is an insertion sort variant that has no inner loop.  Stable:
+/// equal elements keep their order.
 ///
 /// https://en.wikipedia.org/wiki/Gnome_sort
 pub fn gnome_sort<T: Ord>(v: &mut [T]) {
+    gnome_sort_by(v, T::cmp);
+}
+
+/// [`gnome_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn gnome_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
     let mut i = 0;
     while i < v.len() {
-        if i == 0 || v[i] >= v[i - 1] {
+        if i == 0 || compare(&v[i], &v[i - 1]) != Ordering::Less {
             i += 1;
         } else {
-            v.swap(i, i - 1);
+            swap(v, i, i - 1);
             i -= 1;
         }
     }
 }
 
+/// [`gnome_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn gnome_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    gnome_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
 /// **Bubble sort** repeatly swaps elements from left to right until
 /// the largest element is at its position.  This version detects
 /// the position of the last swap, that marks the "already sorted"
-/// region, to avoid unnecessary work on next iterations.
+/// region, to avoid unnecessary work on next iterations.  Stable: equal
+/// elements keep their order.
 pub fn bubble_sort<T: Ord>(v: &mut [T]) {
+    bubble_sort_by(v, T::cmp);
+}
+
+/// [`bubble_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn bubble_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
     let mut n = v.len();
     while n > 0 {
         let mut nmax = 0;
         let mut i = 1;
         while i < n {
-            if v[i - 1] > v[i] {
-                v.swap(i - 1, i);
+            if compare(&v[i - 1], &v[i]) == Ordering::Greater {
+                swap(v, i - 1, i);
                 nmax = i;
             }
             i += 1;
@@ -33,18 +85,136 @@ pub fn bubble_sort<T: Ord>(v: &mut [T]) {
     }
 }
 
+/// [`bubble_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn bubble_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    bubble_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **Cocktail shaker sort** is a bubble sort that alternates passes from left
+/// to right, moving the largest element to the end, with passes from right to
+/// left, moving the smallest to the start, so small elements near the end
+/// ("turtles") also move fast.  As in [`bubble_sort`], the last swap of each
+/// pass marks where the "already sorted" region on that side starts.  Stable:
+/// equal elements keep their order.
+pub fn cocktail_sort<T: Ord>(v: &mut [T]) {
+    cocktail_sort_by(v, T::cmp);
+}
+
+/// [`cocktail_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn cocktail_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    // `v[..start]` and `v[end..]` are already sorted
+    let mut start = 0;
+    let mut end = v.len();
+    while start + 1 < end {
+        let mut last = start;
+        for i in start + 1..end {
+            if compare(&v[i - 1], &v[i]) == Ordering::Greater {
+                swap(v, i - 1, i);
+                last = i;
+            }
+        }
+        end = last;
+
+        let mut first = end;
+        for i in (start + 1..end).rev() {
+            if compare(&v[i - 1], &v[i]) == Ordering::Greater {
+                swap(v, i - 1, i);
+                first = i;
+            }
+        }
+        start = first;
+    }
+}
+
+/// [`cocktail_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn cocktail_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    cocktail_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **Odd-even transposition sort** is a bubble sort that compares and swaps the
+/// pairs starting at odd indices, `(v[1], v[2])`, `(v[3], v[4])`, ..., then the
+/// pairs starting at even indices, repeating until neither pass swaps.  The
+/// pairs of a pass do not overlap, so each could be compared at once by a
+/// processor per pair, in $n$ rounds; done sequentially, it is $O(n^2)$.
+/// Stable: equal elements keep their order.
+pub fn odd_even_sort<T: Ord>(v: &mut [T]) {
+    odd_even_sort_by(v, T::cmp);
+}
+
+/// [`odd_even_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn odd_even_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    let mut swapped = true;
+    while swapped {
+        swapped = false;
+        for start in [1, 0] {
+            for i in (start + 1..v.len()).step_by(2) {
+                if compare(&v[i - 1], &v[i]) == Ordering::Greater {
+                    swap(v, i - 1, i);
+                    swapped = true;
+                }
+            }
+        }
+    }
+}
+
+/// [`odd_even_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn odd_even_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    odd_even_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **Comb sort** is a bubble sort comparing elements a gap apart, starting
+/// with the length of `v` and shrinking it by a factor of 1.3 on each pass,
+/// which moves small elements near the end ("turtles") a long way at once.
+/// Once the gap is down to 1 it goes on as a bubble sort until a pass makes
+/// no swaps.  Not stable: elements a gap apart are swapped over equal ones
+/// between them.
+pub fn comb_sort<T: Ord>(v: &mut [T]) {
+    comb_sort_by(v, T::cmp);
+}
+
+/// [`comb_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn comb_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    let mut gap = v.len();
+    let mut swapped = true;
+    while gap > 1 || swapped {
+        gap = (gap * 10 / 13).max(1);
+        swapped = false;
+        for i in gap..v.len() {
+            if compare(&v[i - gap], &v[i]) == Ordering::Greater {
+                swap(v, i - gap, i);
+                swapped = true;
+            }
+        }
+    }
+}
+
+/// [`comb_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn comb_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    comb_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
 /// **Insertion sort** splits the vector on an "already sorted" region,
 /// initially with only the leftmost element, and a "not sorted" region.
 /// Elements are inserted, one by one, from left to right, from the
-/// "not sorted" region into the "already sorted" region.
+/// "not sorted" region into the "already sorted" region.  Stable: equal
+/// elements keep their order.
 pub fn insertion_sort<T: Ord>(v: &mut [T]) {
+    insertion_sort_by(v, T::cmp);
+}
+
+/// [`insertion_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn insertion_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
     // initially "already sorted" has `1` element, and iterate until we
     // have `v.len()` elements.
     for i in 1..v.len() {
         // `i` is the first not sorted, `j` will be where it should go
         // move left until find the first element larger than the one at `i`
         let mut j = i;
-        while j > 0 && v[j - 1] > v[i] {
+        while j > 0 && compare(&v[j - 1], &v[i]) == Ordering::Greater {
             j -= 1;
         }
 
@@ -55,9 +225,27 @@ pub fn insertion_sort<T: Ord>(v: &mut [T]) {
     }
 }
 
+/// [`insertion_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn insertion_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    insertion_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`insertion_sort`] in decreasing order.  Stable: equal elements keep their
+/// order.
+pub fn insertion_sort_desc<T: Ord>(v: &mut [T]) {
+    insertion_sort_by(v, |a, b| b.cmp(a));
+}
+
 /// **Shell sort** is a variant of insertion sort that moves elements further
-/// away, reducing the distance in each iteraction.
-pub fn shell_sort<T: Ord + Copy>(v: &mut [T]) {
+/// away, reducing the distance in each iteraction.  Not stable: elements a gap
+/// apart are swapped over equal ones between them.
+pub fn shell_sort<T: Ord>(v: &mut [T]) {
+    shell_sort_by(v, T::cmp);
+}
+
+/// [`shell_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn shell_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
     // find the distance between elements
     let mut h = 1;
     while h <= v.len() / 9 {
@@ -69,13 +257,13 @@ pub fn shell_sort<T: Ord + Copy>(v: &mut [T]) {
         // compare `v[i]` with `v[i - h]` (instead of with `v[i - 1]`)
         let mut i = h;
         while i < v.len() {
+            // swap `v[i]` back, `h` at a time, to its place among the
+            // elements `h` apart from it
             let mut j = i;
-            let a = v[i];
-            while j >= h && v[j - h] > a {
-                v[j] = v[j - h];
+            while j >= h && compare(&v[j - h], &v[j]) == Ordering::Greater {
+                swap(v, j - h, j);
                 j -= h;
             }
-            v[j] = a;
             i += 1;
         }
 
@@ -83,273 +271,3834 @@ pub fn shell_sort<T: Ord + Copy>(v: &mut [T]) {
     }
 }
 
+/// [`shell_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn shell_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    shell_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`shell_sort`] in decreasing order.
+pub fn shell_sort_desc<T: Ord>(v: &mut [T]) {
+    shell_sort_by(v, |a, b| b.cmp(a));
+}
+
 /// **Selection sort** is a more direct implementation of the "find the
 /// smallest element and put on the start" idea: from left to right
 /// scan the array for the smallest element on the "not sorted"
 /// region and swap it with the first of the "not sorted", thus growing
-/// the "already sorted" region by one.
+/// the "already sorted" region by one.  Not stable: the swap can move the first
+/// element of the "not sorted" region after an equal one; see
+/// [`selection_sort_opts`] for a stable version.
 pub fn selection_sort<T: Ord>(v: &mut [T]) {
-    for i in 0..v.len() - 1 {
-        // find the smallest element on `v[i+1..]` and swap with the one at `v[i]`.
+    selection_sort_opts(v, false);
+}
+
+/// [`selection_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn selection_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) {
+    selection_sort_opts_by(v, false, compare);
+}
+
+/// [`selection_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn selection_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    selection_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **Selection sort**, as in [`selection_sort`], choosing between swapping
+/// the smallest element with the first of the "not sorted" region, which is
+/// not stable, or with `stable` rotating the region between them instead,
+/// which keeps equal elements in order at the cost of moving more elements.
+pub fn selection_sort_opts<T: Ord>(v: &mut [T], stable: bool) {
+    selection_sort_opts_by(v, stable, T::cmp);
+}
+
+/// [`selection_sort_opts`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+pub fn selection_sort_opts_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    stable: bool,
+    mut compare: F,
+) {
+    for i in 0..v.len().saturating_sub(1) {
+        // find the smallest element on `v[i+1..]` and swap with the one at
+        // `v[i]`, unless `v[i]` already is the smallest
         let mut min = i;
         let mut min_value = &v[i];
-        for j in i + 1..v.len() {
-            if v[j] < *min_value {
+        for (j, x) in v.iter().enumerate().skip(i + 1) {
+            if compare(x, min_value) == Ordering::Less {
                 min = j;
-                min_value = &v[j];
+                min_value = x;
+            }
+        }
+        if stable {
+            v[i..=min].rotate_right(1);
+        } else if min != i {
+            swap(v, i, min);
+        }
+    }
+}
+
+/// Reverse the prefix `v[..=k]`, flipping the top `k + 1` pancakes of the stack.
+fn flip<T>(v: &mut [T], k: usize) {
+    v[..=k].reverse();
+}
+
+/// **Pancake sort** sorts only by reversing prefixes of `v`: like selection
+/// sort it finds the largest element of the "not sorted" region, but moves it
+/// to the end of the region by flipping it to the front, then flipping the
+/// whole region.  Not stable: flips reverse the order of equal elements.
+pub fn pancake_sort<T: Ord>(v: &mut [T]) {
+    pancake_flips_by(v, T::cmp);
+}
+
+/// [`pancake_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn pancake_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) {
+    pancake_flips_by(v, compare);
+}
+
+/// [`pancake_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn pancake_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    pancake_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`pancake_sort`] returning the number of flips done, at most $2(n - 1)$.
+pub fn pancake_sort_counted<T: Ord>(v: &mut [T]) -> usize {
+    pancake_flips_by(v, T::cmp)
+}
+
+/// [`pancake_sort_by`], returning the number of flips done.
+fn pancake_flips_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) -> usize {
+    let mut flips = 0;
+    for end in (1..v.len()).rev() {
+        // the last of the largest elements, already in place if at `end`
+        let max = (0..=end).max_by(|&a, &b| compare(&v[a], &v[b])).unwrap();
+        if max != end {
+            if max != 0 {
+                flip(v, max);
+                flips += 1;
             }
+            flip(v, end);
+            flips += 1;
         }
-        v.swap(i, min);
+    }
+    flips
+}
+
+/// Source of random pivot positions for the Quicksort variants.
+pub trait PivotRng {
+    /// Return an index in `0..n`, with `n > 0`.
+    fn index(&mut self, n: usize) -> usize;
+}
+
+/// The thread-local global `fastrand` generator, used by the Quicksort
+/// variants that do not take a [`PivotRng`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub struct GlobalRng;
+
+#[cfg(all(feature = "fastrand", feature = "std"))]
+impl PivotRng for GlobalRng {
+    fn index(&mut self, n: usize) -> usize {
+        fastrand::usize(..n)
+    }
+}
+
+#[cfg(feature = "fastrand")]
+impl PivotRng for fastrand::Rng {
+    fn index(&mut self, n: usize) -> usize {
+        self.usize(..n)
+    }
+}
+
+/// Not random at all: always the middle of the sub-array, for deterministic
+/// runs without `fastrand`, and for tests of specific partitions.  Sorted and
+/// reversed inputs split evenly, but, unlike random pivots, inputs can be built
+/// against it to take $O(n^2)$ time.
+pub struct MiddlePivot;
+
+impl PivotRng for MiddlePivot {
+    fn index(&mut self, n: usize) -> usize {
+        n / 2
     }
 }
 
 /// **Three-way Quicksort with random pivot**, recurse only on smallest partition
 /// and insertion sort on small sub-arrays.
-/// Does way better than binary Quicksort with many equal elements.
-pub fn quick_sort_3<T: Ord>(mut v: &mut [T]) {
-    fn choose_pivot<T: Ord>(v: &[T]) -> usize {
-        fastrand::usize(..v.len())
-    }
-
-    fn partition<T: Ord>(v: &mut [T]) -> (usize, usize) {
-        let mut mid1 = 1;
-        let mut mid2 = 1;
-        let mut j = 1;
-        while j < v.len() {
-            if v[j] < v[0] {
-                v.swap(mid2, j);
-                v.swap(mid2, mid1);
+/// Does way better than binary Quicksort with many equal elements.  Not stable:
+/// partitioning swaps elements over equal ones.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_3<T: Ord>(v: &mut [T]) {
+    quick_sort_3_with_rng(v, &mut GlobalRng);
+}
+
+/// [`quick_sort_3`] with pivots from a generator seeded with `seed`, so the
+/// same input is always partitioned the same way.
+#[cfg(feature = "fastrand")]
+pub fn quick_sort_3_seeded<T: Ord>(v: &mut [T], seed: u64) {
+    quick_sort_3_with_rng(v, &mut fastrand::Rng::with_seed(seed));
+}
+
+/// Partition `v` in three around the pivot at `v[0]`, as ordered by `compare`:
+/// the elements smaller than it first, then those equal to it, pivot included,
+/// then the larger ones.  Return the range of the equal ones as `(start, end)`.
+fn partition_3_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: &mut F) -> (usize, usize) {
+    let mut mid1 = 1;
+    let mut mid2 = 1;
+    let mut j = 1;
+    while j < v.len() {
+        match compare(&v[j], &v[0]) {
+            Ordering::Less => {
+                swap(v, mid2, j);
+                swap(v, mid2, mid1);
                 mid1 += 1;
                 mid2 += 1;
-            } else if v[j] == v[0] {
-                v.swap(mid2, j);
+            }
+            Ordering::Equal => {
+                swap(v, mid2, j);
                 mid2 += 1;
             }
-            j += 1;
+            Ordering::Greater => {}
         }
-        v.swap(mid1 - 1, 0);
-        (mid1 - 1, mid2)
+        j += 1;
     }
+    swap(v, mid1 - 1, 0);
+    (mid1 - 1, mid2)
+}
 
-    while v.len() > 30 {
-        let pivot = choose_pivot(v);
-        v.swap(pivot, 0);
+/// [`quick_sort_3`] taking pivots from the given `rng`.
+pub fn quick_sort_3_with_rng<T: Ord, R: PivotRng>(v: &mut [T], rng: &mut R) {
+    quick_sort_3_by_with_rng(v, T::cmp, rng);
+}
 
-        let (mid1, mid2) = partition(v);
-        if mid1 < v.len() - mid2 {
-            quick_sort_3(&mut v[..mid1]);
-            v = &mut v[mid2..];
-        } else {
-            quick_sort_3(&mut v[mid2..]);
-            v = &mut v[..mid1];
-        }
-    }
+/// [`quick_sort_3`] ordering elements with `compare`, like [`slice::sort_by`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_3_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) {
+    quick_sort_3_by_with_rng(v, compare, &mut GlobalRng);
+}
 
-    insertion_sort(v);
+/// [`quick_sort_3`] in decreasing order.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_3_desc<T: Ord>(v: &mut [T]) {
+    quick_sort_3_by(v, |a, b| b.cmp(a));
 }
 
-/// **Binary Quicksort with random pivot**, recurse only on smallest partition
-/// and insertion sort on small sub-arrays.
-pub fn quick_sort<T: Ord>(mut v: &mut [T]) {
-    fn choose_pivot<T: Ord>(v: &[T]) -> usize {
-        fastrand::usize(..v.len())
-    }
+/// [`quick_sort_3_by`] taking pivots from the given `rng`.
+pub fn quick_sort_3_by_with_rng<T, F: FnMut(&T, &T) -> Ordering, R: PivotRng>(
+    v: &mut [T],
+    mut compare: F,
+    rng: &mut R,
+) {
+    fn sort<T, F: FnMut(&T, &T) -> Ordering, R: PivotRng>(
+        mut v: &mut [T],
+        compare: &mut F,
+        rng: &mut R,
+    ) {
+        while v.len() > 30 {
+            let pivot = rng.index(v.len());
+            swap(v, pivot, 0);
 
-    fn partition<T: Ord>(v: &mut [T]) -> usize {
-        let mut i = 1;
-        let mut j = 1;
-        while j < v.len() {
-            if v[j] < v[0] {
-                v.swap(i, j);
-                i += 1;
+            let (mid1, mid2) = partition_3_by(v, compare);
+            if mid1 < v.len() - mid2 {
+                sort(&mut v[..mid1], compare, rng);
+                v = &mut v[mid2..];
+            } else {
+                sort(&mut v[mid2..], compare, rng);
+                v = &mut v[..mid1];
             }
-            j += 1;
         }
-        v.swap(i - 1, 0);
-        i - 1
+
+        insertion_sort_by(v, compare);
     }
 
-    while v.len() > 30 {
-        let pivot = choose_pivot(v);
-        v.swap(pivot, 0);
+    sort(v, &mut compare, rng);
+}
 
-        let mid = partition(v);
-        let n = v.len();
-        if mid < n - mid {
-            quick_sort(&mut v[..mid]);
-            if mid < n {
-                v = &mut v[mid + 1..];
-            } else {
-                break;
-            }
+/// **Three-way Quicksort with random pivot** comparing the keys that `f`
+/// returns for each element, like [`slice::sort_unstable_by_key`].  Keys are
+/// not cached, `f` is called again on each comparison, so it should be cheap;
+/// for expensive keys see [`slice::sort_by_cached_key`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    quick_sort_by_key_with_rng(v, f, &mut GlobalRng);
+}
+
+/// [`quick_sort_by_key`] taking pivots from the given `rng`.
+pub fn quick_sort_by_key_with_rng<T, K: Ord, F: FnMut(&T) -> K, R: PivotRng>(
+    v: &mut [T],
+    mut f: F,
+    rng: &mut R,
+) {
+    quick_sort_3_by_with_rng(v, |a, b| f(a).cmp(&f(b)), rng);
+}
+
+/// **Quickselect** with random pivot: rearrange `v` so that `v[k]` holds the
+/// element that would be there after sorting it, with the elements before it
+/// not larger and those after it not smaller, and return it.  Partitions like
+/// [`quick_sort_3`] but only goes on into the side holding `k`, for an average
+/// of $O(n)$.  Panics if `k` is out of bounds.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quickselect<T: Ord>(v: &mut [T], k: usize) -> &T {
+    quickselect_with_rng(v, k, &mut GlobalRng)
+}
+
+/// [`quickselect`] taking pivots from the given `rng`.
+pub fn quickselect_with_rng<'a, T: Ord, R: PivotRng>(
+    v: &'a mut [T],
+    k: usize,
+    rng: &mut R,
+) -> &'a T {
+    assert!(
+        k < v.len(),
+        "index {k} out of bounds for length {}",
+        v.len()
+    );
+
+    let mut start = 0;
+    let mut end = v.len();
+    while end - start > 1 {
+        let w = &mut v[start..end];
+        let pivot = rng.index(w.len());
+        swap(w, pivot, 0);
+
+        let (mid1, mid2) = partition_3_by(w, &mut T::cmp);
+        if k < start + mid1 {
+            end = start + mid1;
+        } else if k < start + mid2 {
+            break;
         } else {
-            if mid < n {
-                quick_sort(&mut v[mid + 1..]);
-            }
-            v = &mut v[..mid];
+            start += mid2;
         }
     }
 
-    insertion_sort(v);
+    &v[k]
 }
 
-/// Sort by converting the vector into a heap and repeatedly removing the largest element.
-pub fn heap_sort<T: Ord>(v: &mut [T]) {
-    // move the element at `v[start]` down, swapping with the smallest children,
-    // as much as possible, to find its final position in the heap.
-    fn sift_down<T: Ord>(v: &mut [T], start: usize) {
-        let mut i = start;
-        loop {
-            let mut child = i * 2 + 1;
-            if child >= v.len() {
-                break;
-            } else if child + 1 < v.len() && v[child + 1] > v[child] {
-                child += 1;
-            }
+/// Partition `v` around the pivot at `v[0]`, moving the elements smaller than it
+/// before it and the others after it, and return the final index of the pivot,
+/// always in `0..v.len()`.
+fn partition<T: Ord>(v: &mut [T]) -> usize {
+    partition_by(v, &mut T::cmp)
+}
 
-            if v[i] < v[child] {
-                v.swap(i, child);
-                i = child;
+/// [`partition`] ordering elements with `compare`.
+fn partition_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: &mut F) -> usize {
+    let mut i = 1;
+    let mut j = 1;
+    while j < v.len() {
+        if compare(&v[j], &v[0]) == Ordering::Less {
+            swap(v, i, j);
+            i += 1;
+        }
+        j += 1;
+    }
+    swap(v, i - 1, 0);
+    i - 1
+}
+
+/// **Binary Quicksort with random pivot**, recurse only on smallest partition
+/// and insertion sort on small sub-arrays.  Not stable: partitioning swaps
+/// elements over equal ones.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort<T: Ord>(v: &mut [T]) {
+    quick_sort_with_rng(v, &mut GlobalRng);
+}
+
+/// [`quick_sort`] with pivots from a generator seeded with `seed`, so the
+/// same input is always partitioned the same way.
+#[cfg(feature = "fastrand")]
+pub fn quick_sort_seeded<T: Ord>(v: &mut [T], seed: u64) {
+    quick_sort_with_rng(v, &mut fastrand::Rng::with_seed(seed));
+}
+
+/// [`quick_sort`] taking pivots from the given `rng`.
+pub fn quick_sort_with_rng<T: Ord, R: PivotRng>(v: &mut [T], rng: &mut R) {
+    quick_sort_by_with_rng(v, T::cmp, rng);
+}
+
+/// [`quick_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) {
+    quick_sort_by_with_rng(v, compare, &mut GlobalRng);
+}
+
+/// [`quick_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_cached_key`]: keys are computed once per element, and
+/// then the indices of the elements sorted by them, ties broken by index, so
+/// the order of equal keys is kept.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_by_cached_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    let keys: Vec<K> = v.iter().map(f).collect();
+    let mut perm: Vec<usize> = (0..v.len()).collect();
+    quick_sort_by(&mut perm, |&a, &b| keys[a].cmp(&keys[b]).then(a.cmp(&b)));
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// [`quick_sort`] in decreasing order.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_desc<T: Ord>(v: &mut [T]) {
+    quick_sort_by(v, |a, b| b.cmp(a));
+}
+
+/// [`quick_sort_by`] taking pivots from the given `rng`.
+pub fn quick_sort_by_with_rng<T, F: FnMut(&T, &T) -> Ordering, R: PivotRng>(
+    v: &mut [T],
+    mut compare: F,
+    rng: &mut R,
+) {
+    fn sort<T, F: FnMut(&T, &T) -> Ordering, R: PivotRng>(
+        mut v: &mut [T],
+        compare: &mut F,
+        rng: &mut R,
+    ) {
+        while v.len() > 30 {
+            let pivot = rng.index(v.len());
+            swap(v, pivot, 0);
+
+            let mid = partition_by(v, compare);
+            let (left, right) = v.split_at_mut(mid);
+            let right = &mut right[1..];
+            if left.len() < right.len() {
+                sort(left, compare, rng);
+                v = right;
             } else {
-                break;
+                sort(right, compare, rng);
+                v = left;
             }
         }
-    }
 
-    // transform `v` into a heap with largest element on `v[0]`
-    for i in (0..=v.len() / 2).rev() {
-        sift_down(v, i);
+        insertion_sort_by(v, compare);
     }
 
-    // iterating from the last element to the first, swap the
-    // largest `v[0]` element with it and rebuild the heap state.
-    for i in (1..v.len()).rev() {
-        v.swap(0, i);
-        sift_down(&mut v[..i], 0);
-    }
+    sort(v, &mut compare, rng);
 }
 
-/// Merge `from[..half]` and `from[half..]` into `to[..from.len()]`.
-fn merge<T: Ord + Clone>(from: &[T], half: usize, to: &mut [T]) {
-    let mut i = 0;
-    let mut j = half;
-    for k in 0..from.len() {
-        if i < half && (j >= from.len() || from[i] <= from[j]) {
-            to[k] = from[i].clone();
-            i += 1;
+/// Move the element at `v[start]` down, swapping with the larger of its
+/// children, as ordered by `compare`, as much as possible, to find its final
+/// position in the heap.  The heap keeps its largest element on `v[0]`; with a
+/// reversed `compare` that is the smallest.
+fn sift_down<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], start: usize, compare: &mut F) {
+    let mut i = start;
+    loop {
+        let mut child = i * 2 + 1;
+        if child >= v.len() {
+            break;
+        } else if child + 1 < v.len() && compare(&v[child + 1], &v[child]) == Ordering::Greater {
+            child += 1;
+        }
+
+        if compare(&v[child], &v[i]) == Ordering::Greater {
+            swap(v, i, child);
+            i = child;
         } else {
-            to[k] = from[j].clone();
-            j += 1;
+            break;
         }
     }
 }
 
-/// **Merge sort** by breaking the array in half, recursing, and
-/// following this way *top down*.
-pub fn merge_sort_top_down<T: Ord + Clone>(v: &mut [T]) {
-    // compute the recursive merge sort of `w` and store the result into `v`
-    fn split_merge<T: Ord + Clone>(w: &mut [T], v: &mut [T]) {
-        if w.len() > 1 {
-            let half = w.len() / 2;
-            split_merge(&mut v[..half], &mut w[..half]);
-            split_merge(&mut v[half..], &mut w[half..]);
-            merge(w, half, v);
+/// [`quick_sort`] recording, for each partition, the start and length of the
+/// partitioned sub-array and the final index of its pivot, all relative to `v`,
+/// to diagnose unbalanced partitions.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_trace<T: Ord>(v: &mut [T]) -> Vec<(usize, usize, usize)> {
+    quick_sort_trace_with_rng(v, &mut GlobalRng)
+}
+
+/// [`quick_sort_trace`] taking pivots from the given `rng`.
+#[cfg(feature = "alloc")]
+pub fn quick_sort_trace_with_rng<T: Ord, R: PivotRng>(
+    v: &mut [T],
+    rng: &mut R,
+) -> Vec<(usize, usize, usize)> {
+    fn traced<T: Ord, R: PivotRng>(
+        mut v: &mut [T],
+        mut start: usize,
+        rng: &mut R,
+        trace: &mut Vec<(usize, usize, usize)>,
+    ) {
+        while v.len() > 30 {
+            let pivot = rng.index(v.len());
+            swap(v, pivot, 0);
+
+            let mid = partition(v);
+            trace.push((start, v.len(), start + mid));
+            if mid < v.len() - mid {
+                traced(&mut v[..mid], start, rng, trace);
+                v = &mut v[mid + 1..];
+                start += mid + 1;
+            } else {
+                traced(&mut v[mid + 1..], start + mid + 1, rng, trace);
+                v = &mut v[..mid];
+            }
         }
+
+        insertion_sort(v);
     }
 
-    let mut w: Vec<_> = v.iter().cloned().collect();
-    split_merge(&mut w, v);
+    let mut trace = vec![];
+    traced(v, 0, rng, &mut trace);
+    trace
 }
 
-/// **Merge sort** top down, using insertion sort for small sub arrays.
-pub fn merge_sort_top_down_insert<T: Ord + Clone>(v: &mut [T]) {
-    fn split_merge<T: Ord + Clone>(w: &mut [T], v: &mut [T]) {
-        if w.len() <= 16 {
-            insertion_sort(v);
-        } else {
-            let half = w.len() / 2;
-            split_merge(&mut v[..half], &mut w[..half]);
-            split_merge(&mut v[half..], &mut w[half..]);
-            merge(w, half, v);
-        }
-    }
+/// How balanced the partitions of a Quicksort were, from
+/// [`quick_sort_balance_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceStats {
+    /// Deepest nesting of partitions, counting those the loop does in place of
+    /// recursion.  About $\log_2 n$ when balanced, up to $n$ when not.
+    pub max_depth: usize,
+    /// Number of partitions done.
+    pub partitions: usize,
+    /// Smallest fraction of a sub-array that went to the smaller side of its
+    /// partition, `0.0` for the most lopsided split, `0.5` for a perfect one
+    /// (and when there were no partitions).
+    pub worst_ratio: f64,
+}
 
-    let mut w: Vec<_> = v.iter().cloned().collect();
-    split_merge(&mut w, v);
+/// [`quick_sort`] measuring how balanced its partitions were.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_balance_stats<T: Ord>(v: &mut [T]) -> BalanceStats {
+    quick_sort_balance_stats_with_rng(v, &mut GlobalRng)
 }
 
-/// **Merge sort** by merging pairs, then four elements, so forth,
-/// doubling, going *bottom up* until finally both halves of the
-/// array are merged in the whole.
-pub fn merge_sort_bottom_up<T: Ord + Clone>(v: &mut [T]) {
-    let mut w: Vec<_> = v.iter().cloned().collect();
+/// [`quick_sort_balance_stats`] taking pivots from the given `rng`.
+pub fn quick_sort_balance_stats_with_rng<T: Ord, R: PivotRng>(
+    v: &mut [T],
+    rng: &mut R,
+) -> BalanceStats {
+    fn measured<T: Ord, R: PivotRng>(
+        mut v: &mut [T],
+        mut depth: usize,
+        rng: &mut R,
+        stats: &mut BalanceStats,
+    ) {
+        while v.len() > 30 {
+            let pivot = rng.index(v.len());
+            swap(v, pivot, 0);
 
-    let n = v.len();
-    let mut v_to_w = true;
+            let mid = partition(v);
+            let n = v.len();
+            depth += 1;
+            stats.max_depth = stats.max_depth.max(depth);
+            stats.partitions += 1;
+            let ratio = mid.min(n - 1 - mid) as f64 / n as f64;
+            stats.worst_ratio = stats.worst_ratio.min(ratio);
 
-    let mut width = 1;
-    while width < n {
-        let mut i = 0;
-        while i < n {
-            let end = (i + 2 * width).min(n);
-            if v_to_w {
-                merge(&v[i..end], width, &mut w[i..end]);
+            if mid < n - mid {
+                measured(&mut v[..mid], depth, rng, stats);
+                v = &mut v[mid + 1..];
             } else {
-                merge(&w[i..end], width, &mut v[i..end]);
+                measured(&mut v[mid + 1..], depth, rng, stats);
+                v = &mut v[..mid];
             }
-            i += 2 * width;
         }
 
-        v_to_w = !v_to_w;
-        width *= 2;
+        insertion_sort(v);
     }
 
-    if !v_to_w {
-        v.clone_from_slice(&w);
-    }
+    let mut stats = BalanceStats {
+        max_depth: 0,
+        partitions: 0,
+        worst_ratio: 0.5,
+    };
+    measured(v, 0, rng, &mut stats);
+    stats
 }
 
-pub fn merge_sort_bottom_up_insert<T: Ord + Clone>(v: &mut [T]) {
-    let mut w: Vec<_> = v.iter().cloned().collect();
+/// **Binary Quicksort with sampled median pivot**: at each level pick `sample`
+/// random elements and use their median as the pivot, which gives better
+/// balanced partitions than a single random pick at a small cost.  When a
+/// partition still leaves less than 1/8 of the elements on one side, the next
+/// pivot is the [`median_of_medians`] instead, so an unlucky run of samples
+/// cannot make it quadratic.  Recurse only on smallest partition and insertion
+/// sort on small sub-arrays.  Not stable, as [`quick_sort`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_sampled_median<T: Ord>(v: &mut [T], sample: usize) {
+    quick_sort_sampled_median_with_rng(v, sample, &mut GlobalRng);
+}
 
-    let n = v.len();
+/// [`quick_sort_sampled_median`] taking the samples from the given `rng`.
+#[cfg(feature = "alloc")]
+pub fn quick_sort_sampled_median_with_rng<T: Ord, R: PivotRng>(
+    mut v: &mut [T],
+    sample: usize,
+    rng: &mut R,
+) {
+    // index of the median of `sample` random elements of `v`
+    fn choose_pivot<T: Ord, R: PivotRng>(v: &[T], sample: usize, rng: &mut R) -> usize {
+        let mut indices: Vec<usize> = core::iter::repeat_with(|| rng.index(v.len()))
+            .take(sample.clamp(1, v.len()))
+            .collect();
+        indices.sort_unstable_by(|&a, &b| v[a].cmp(&v[b]));
+        indices[indices.len() / 2]
+    }
 
-    // swap between v and w to avoid too much cloning
-    let mut v_to_w = true;
+    let mut unbalanced = false;
+    while v.len() > 30 {
+        let pivot = if unbalanced {
+            median_of_medians(v)
+        } else {
+            choose_pivot(v, sample, rng)
+        };
+        swap(v, pivot, 0);
 
-    // the initial value defines the sizes of the initial insertion sort
-    let mut width = 8;
+        let mid = partition(v);
+        unbalanced = mid.min(v.len() - 1 - mid) < v.len() / 8;
+        if mid < v.len() - mid {
+            quick_sort_sampled_median_with_rng(&mut v[..mid], sample, rng);
+            v = &mut v[mid + 1..];
+        } else {
+            quick_sort_sampled_median_with_rng(&mut v[mid + 1..], sample, rng);
+            v = &mut v[..mid];
+        }
+    }
 
-    // a first round of insertion sort...
-    for i in (0..n).step_by(2 * width) {
-        let end = (i + 2 * width).min(n);
-        insertion_sort(&mut v[i..end]);
+    insertion_sort(v);
+}
+
+/// Index of the median of `v[a]`, `v[b]` and `v[c]`.
+fn median_of_3<T: Ord>(v: &[T], a: usize, b: usize, c: usize) -> usize {
+    median_of_3_by(v, a, b, c, &mut T::cmp)
+}
+
+/// [`median_of_3`] ordering elements with `compare`.
+fn median_of_3_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &[T],
+    a: usize,
+    b: usize,
+    c: usize,
+    compare: &mut F,
+) -> usize {
+    let mut le = |x: usize, y: usize| compare(&v[x], &v[y]) != Ordering::Greater;
+    if le(a, b) == le(b, c) {
+        b
+    } else if le(b, a) == le(a, c) {
+        a
+    } else {
+        c
     }
+}
 
-    // ...then increasing merging groups, until all array is merge
-    while width < n {
-        for i in (0..n).step_by(2 * width) {
-            let end = (i + 2 * width).min(n);
-            if v_to_w {
-                merge(&v[i..end], width, &mut w[i..end]);
+/// **Binary Quicksort with hinted pivot** (experimental): the pivot is the
+/// median of the first, the last, and a hinted element of each sub-array.
+/// The hint is random at the top, and after a balanced partition the children
+/// are hinted to their middle element, which needs no random numbers and
+/// splits structured data well; after an unbalanced one the hint is random
+/// again.  Recurse only on smallest partition and insertion sort on small
+/// sub-arrays.  Not stable, as [`quick_sort`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_hinted<T: Ord>(v: &mut [T]) {
+    quick_sort_hinted_with_rng(v, &mut GlobalRng);
+}
+
+/// [`quick_sort_hinted`] taking the random hints from the given `rng`.
+pub fn quick_sort_hinted_with_rng<T: Ord, R: PivotRng>(v: &mut [T], rng: &mut R) {
+    fn hinted<T: Ord, R: PivotRng>(mut v: &mut [T], mut hint: Option<usize>, rng: &mut R) {
+        while v.len() > 30 {
+            let n = v.len();
+            let h = hint.unwrap_or_else(|| rng.index(n));
+            let pivot = median_of_3(v, 0, h, n - 1);
+            swap(v, pivot, 0);
+
+            let mid = partition(v);
+            let balanced = mid.min(n - 1 - mid) >= n / 8;
+            let child_hint = |len: usize| balanced.then_some(len / 2);
+            if mid < n - mid {
+                hinted(&mut v[..mid], child_hint(mid), rng);
+                v = &mut v[mid + 1..];
             } else {
-                merge(&w[i..end], width, &mut v[i..end]);
+                hinted(&mut v[mid + 1..], child_hint(n - mid - 1), rng);
+                v = &mut v[..mid];
             }
+            hint = child_hint(v.len());
         }
-        v_to_w = !v_to_w;
-        width *= 2;
-    }
 
-    // last clone if final iteration left the sorted result in w
-    if !v_to_w {
-        v.clone_from_slice(&w);
+        insertion_sort(v);
     }
+
+    hinted(v, None, rng);
 }
 
-pub fn native_sort<T: Ord>(v: &mut [T]) {
-    v.sort();
+/// **Three-way Quicksort with random pivot** that stops partitioning after
+/// `max_depth` levels of recursion and insertion sorts whatever sub-arrays are
+/// left, whatever their size.  There is no size cutoff: with a large enough
+/// `max_depth` partitioning goes down to single elements, while a `max_depth`
+/// of `0` is a plain insertion sort.  Recurse only on the smallest partition,
+/// looping on the other, so the stack stays logarithmic even for a huge
+/// `max_depth`, and elements equal to the pivot are done with at once, so equal
+/// elements do not go a level deeper each.  Not stable, as [`quick_sort`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn quick_sort_depth_cutoff<T: Ord>(v: &mut [T], max_depth: usize) {
+    quick_sort_depth_cutoff_with_rng(v, max_depth, &mut GlobalRng);
 }
 
-pub fn native_unstable_sort<T: Ord>(v: &mut [T]) {
+/// [`quick_sort_depth_cutoff`] taking pivots from the given `rng`.
+pub fn quick_sort_depth_cutoff_with_rng<T: Ord, R: PivotRng>(
+    mut v: &mut [T],
+    mut max_depth: usize,
+    rng: &mut R,
+) {
+    while max_depth > 0 && v.len() > 1 {
+        let pivot = rng.index(v.len());
+        swap(v, pivot, 0);
+
+        let (mid1, mid2) = partition_3_by(v, &mut T::cmp);
+        let (left, right) = v.split_at_mut(mid1);
+        let right = &mut right[mid2 - mid1..];
+        max_depth -= 1;
+        if left.len() < right.len() {
+            quick_sort_depth_cutoff_with_rng(left, max_depth, rng);
+            v = right;
+        } else {
+            quick_sort_depth_cutoff_with_rng(right, max_depth, rng);
+            v = left;
+        }
+    }
+
+    insertion_sort(v);
+}
+
+/// **Binary Quicksort with median-of-three pivot**: the pivot is the median of
+/// the first, middle and last elements of each sub-array, which needs no
+/// random numbers and splits sorted and reversed inputs evenly, where a first
+/// element pivot takes $O(n^2)$, but inputs increasing up to the middle and
+/// then decreasing, like a pipe organ, still take $O(n^2)$.  Recurse only on
+/// smallest partition and insertion sort on small sub-arrays.  Not stable, as
+/// [`quick_sort`].
+pub fn quick_sort_median_of_3<T: Ord>(mut v: &mut [T]) {
+    while v.len() > 30 {
+        let n = v.len();
+        let pivot = median_of_3(v, 0, n / 2, n - 1);
+        swap(v, pivot, 0);
+
+        let mid = partition(v);
+        let (left, right) = v.split_at_mut(mid);
+        let right = &mut right[1..];
+        if left.len() < right.len() {
+            quick_sort_median_of_3(left);
+            v = right;
+        } else {
+            quick_sort_median_of_3(right);
+            v = left;
+        }
+    }
+
+    insertion_sort(v);
+}
+
+/// Rearrange `v` and return the index of an element between its 30th and 70th
+/// percentiles, in $O(n)$: insertion sort each group of 5 elements, move the
+/// median of each group to the front of `v`, and select the median of those
+/// medians with [`select_median_of_medians`].
+fn median_of_medians<T: Ord>(v: &mut [T]) -> usize {
+    if v.len() <= 5 {
+        insertion_sort(v);
+        return v.len() / 2;
+    }
+
+    let groups = v.len() / 5;
+    for g in 0..groups {
+        insertion_sort(&mut v[g * 5..g * 5 + 5]);
+        swap(v, g, g * 5 + 2);
+    }
+    select_median_of_medians(&mut v[..groups], groups / 2);
+    groups / 2
+}
+
+/// Rearrange `v` so that `v[k]` holds the element that would be there after
+/// sorting it, as [`quickselect`] does, in $O(n)$ time even in the worst case,
+/// partitioning in three around the [`median_of_medians`].
+fn select_median_of_medians<T: Ord>(mut v: &mut [T], mut k: usize) {
+    while v.len() > 5 {
+        let pivot = median_of_medians(v);
+        swap(v, pivot, 0);
+
+        let (lt, gt) = partition_3_by(v, &mut T::cmp);
+        if k < lt {
+            v = &mut v[..lt];
+        } else if k >= gt {
+            v = &mut v[gt..];
+            k -= gt;
+        } else {
+            return;
+        }
+    }
+
+    insertion_sort(v);
+}
+
+/// **Three-way Quicksort with median-of-medians pivot**: the pivot is found by
+/// the median of medians selection, always between the 30th and 70th
+/// percentiles of the sub-array, so partitions are never worse than 30/70 and
+/// the sort runs in $O(n \log n)$ even in the worst case, with no random
+/// numbers, but with a larger constant than random pivots.  Recurse only on
+/// smallest partition and insertion sort on small sub-arrays.  Not stable, as
+/// [`quick_sort`].
+pub fn quick_sort_median_of_medians<T: Ord>(mut v: &mut [T]) {
+    while v.len() > 30 {
+        let pivot = median_of_medians(v);
+        swap(v, pivot, 0);
+
+        let (lt, gt) = partition_3_by(v, &mut T::cmp);
+        let (left, right) = v.split_at_mut(lt);
+        let right = &mut right[gt - lt..];
+        if left.len() < right.len() {
+            quick_sort_median_of_medians(left);
+            v = right;
+        } else {
+            quick_sort_median_of_medians(right);
+            v = left;
+        }
+    }
+
+    insertion_sort(v);
+}
+
+/// **Introsort**: binary Quicksort with the median of the first, middle and
+/// last elements as pivot, recursing only on the smallest partition and
+/// insertion sorting small sub-arrays, that switches to [`heap_sort`] for any
+/// sub-array still being partitioned after $2 \log_2 n$ levels.  Keeps the
+/// speed of Quicksort on most inputs with an $O(n \log n)$ worst case, and
+/// needs no random numbers.  Not stable, as [`quick_sort`] and [`heap_sort`].
+pub fn intro_sort<T: Ord>(v: &mut [T]) {
+    intro_sort_by(v, T::cmp);
+}
+
+/// [`intro_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn intro_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    fn intro<T, F: FnMut(&T, &T) -> Ordering>(mut v: &mut [T], mut depth: usize, compare: &mut F) {
+        while v.len() > 30 {
+            if depth == 0 {
+                heap_sort_by(v, &mut *compare);
+                return;
+            }
+            depth -= 1;
+
+            let n = v.len();
+            let pivot = median_of_3_by(v, 0, n / 2, n - 1, compare);
+            swap(v, pivot, 0);
+
+            let mid = partition_by(v, compare);
+            if mid < n - mid {
+                intro(&mut v[..mid], depth, compare);
+                v = &mut v[mid + 1..];
+            } else {
+                intro(&mut v[mid + 1..], depth, compare);
+                v = &mut v[..mid];
+            }
+        }
+
+        insertion_sort_by(v, compare);
+    }
+
+    let depth = 2 * v.len().max(1).ilog2() as usize;
+    intro(v, depth, &mut compare);
+}
+
+/// [`intro_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn intro_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    intro_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// Move the element at `v[start]` up, swapping with its parent, as much as
+/// possible, to find its final position in the heap.  `compare` as in
+/// [`sift_down`].
+fn sift_up<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], start: usize, compare: &mut F) {
+    let mut i = start;
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if compare(&v[i], &v[parent]) == Ordering::Greater {
+            swap(v, i, parent);
+            i = parent;
+        } else {
+            break;
+        }
+    }
+}
+
+/// Sort by converting the vector into a heap and repeatedly removing the largest element.
+/// Not stable: moving elements in and out of the heap reorders equal ones.
+pub fn heap_sort<T: Ord>(v: &mut [T]) {
+    heap_sort_by(v, T::cmp);
+}
+
+/// [`heap_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn heap_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    // transform `v` into a heap with largest element on `v[0]`
+    for i in (0..=v.len() / 2).rev() {
+        sift_down(v, i, &mut compare);
+    }
+
+    // iterating from the last element to the first, swap the
+    // largest `v[0]` element with it and rebuild the heap state.
+    for i in (1..v.len()).rev() {
+        swap(v, 0, i);
+        sift_down(&mut v[..i], 0, &mut compare);
+    }
+}
+
+/// [`heap_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn heap_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    heap_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`heap_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_cached_key`]: keys are computed once per element, and
+/// then the indices of the elements sorted by them, ties broken by index, so
+/// the order of equal keys is kept.
+#[cfg(feature = "alloc")]
+pub fn heap_sort_by_cached_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    let keys: Vec<K> = v.iter().map(f).collect();
+    let mut perm: Vec<usize> = (0..v.len()).collect();
+    heap_sort_by(&mut perm, |&a, &b| keys[a].cmp(&keys[b]).then(a.cmp(&b)));
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// Sort only the `count` largest elements of `v` into their final positions at
+/// its end, like the first `count` steps of [`heap_sort`].  The rest of `v`,
+/// `v[..v.len() - count]`, is left as a heap with the largest element on top,
+/// so further elements can be extracted later with [`heap_extract_largest`],
+/// without building the heap again.  A `count` larger than `v.len()` sorts all.
+pub fn heap_sort_partial<T: Ord>(v: &mut [T], count: usize) {
+    for i in (0..=v.len() / 2).rev() {
+        sift_down(v, i, &mut T::cmp);
+    }
+    heap_extract_largest(v, count);
+}
+
+/// Continue a [`heap_sort_partial`]: move the `count` largest elements of the
+/// heap `v` into their sorted positions at its end, leaving the rest a heap.
+/// Given `v` after `heap_sort_partial(v, k)`, calling this on
+/// `&mut v[..v.len() - k]` sorts the next `count` largest elements.
+pub fn heap_extract_largest<T: Ord>(v: &mut [T], count: usize) {
+    let n = v.len();
+    for i in (n.saturating_sub(count).max(1)..n).rev() {
+        swap(v, 0, i);
+        sift_down(&mut v[..i], 0, &mut T::cmp);
+    }
+}
+
+/// Like [`heap_sort`], but builds the heap *top down*, inserting elements one
+/// by one and moving each up to its position, which takes $O(n \log n)$ instead
+/// of the $O(n)$ of building it bottom up.  For comparison only.  Not stable,
+/// as [`heap_sort`].
+pub fn heap_sort_topdown_build<T: Ord>(v: &mut [T]) {
+    // grow the heap from `v[..1]` to the whole `v`
+    for i in 1..v.len() {
+        sift_up(v, i, &mut T::cmp);
+    }
+
+    for i in (1..v.len()).rev() {
+        swap(v, 0, i);
+        sift_down(&mut v[..i], 0, &mut T::cmp);
+    }
+}
+
+/// [`heap_sort`] in decreasing order, converting the vector into a heap with
+/// the smallest element on top and repeatedly moving it to the end, so there
+/// is no need to reverse the result of [`heap_sort`].  Not stable, as
+/// [`heap_sort`].
+pub fn heap_sort_desc<T: Ord>(v: &mut [T]) {
+    heap_sort_by(v, |a, b| b.cmp(a));
+}
+
+/// [`heap_sort_desc`] under its former name.
+#[deprecated(note = "renamed to `heap_sort_desc`, as the other `*_desc` sorts")]
+pub fn heap_sort_descending<T: Ord>(v: &mut [T]) {
+    heap_sort_desc(v);
+}
+
+/// Leonardo numbers, $L(0) = L(1) = 1$ and $L(k) = L(k - 1) + L(k - 2) + 1$,
+/// the sizes of the trees of [`smooth_sort`], saturating once too large for
+/// any slice.
+const LEONARDO: [usize; 128] = {
+    let mut l = [1usize; 128];
+    let mut k = 2;
+    while k < 128 {
+        l[k] = l[k - 1].saturating_add(l[k - 2]).saturating_add(1);
+        k += 1;
+    }
+    l
+};
+
+/// Move the root at `v[root]` of a Leonardo tree of order `order` down,
+/// swapping it with the larger of its children, until it is not smaller than
+/// them.  A tree of order $k \ge 2$ has its root last, preceded by its right
+/// child, of order $k - 2$, preceded by its left child, of order $k - 1$.
+fn leonardo_sift<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut root: usize,
+    mut order: usize,
+    compare: &mut F,
+) {
+    while order >= 2 {
+        let right = root - 1;
+        let left = right - LEONARDO[order - 2];
+        let (child, child_order) = if compare(&v[left], &v[right]) == Ordering::Greater {
+            (left, order - 1)
+        } else {
+            (right, order - 2)
+        };
+        if compare(&v[root], &v[child]) != Ordering::Less {
+            break;
+        }
+        swap(v, root, child);
+        root = child;
+        order = child_order;
+    }
+}
+
+/// Restore the order of the roots of the Leonardo trees of [`smooth_sort`],
+/// after the root at `v[root]` of the rightmost tree in `orders` changed: move
+/// it left along the roots, each the largest of its tree, while the previous
+/// root is larger than it and its children, and then sift it down in the tree
+/// where it stops.  Bit $k$ of `orders` is set if there is a tree of order
+/// $k$, with the trees in decreasing order from left to right.
+fn leonardo_rectify<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut root: usize,
+    orders: u128,
+    compare: &mut F,
+) {
+    let mut order = orders.trailing_zeros() as usize;
+    let mut rest = orders & !(1 << order);
+    while rest != 0 {
+        let previous = root - LEONARDO[order];
+        // the largest of the root and its children, which the previous root
+        // has to be larger than to move it here
+        let mut largest = root;
+        if order >= 2 {
+            let right = root - 1;
+            let left = right - LEONARDO[order - 2];
+            for child in [left, right] {
+                if compare(&v[child], &v[largest]) == Ordering::Greater {
+                    largest = child;
+                }
+            }
+        }
+        if compare(&v[previous], &v[largest]) != Ordering::Greater {
+            break;
+        }
+        swap(v, previous, root);
+        root = previous;
+        order = rest.trailing_zeros() as usize;
+        rest &= !(1 << order);
+    }
+    leonardo_sift(v, root, order, compare);
+}
+
+/// **Smoothsort**, Dijkstra's adaptive heapsort: grow from left to right a
+/// forest of max-heaps shaped as Leonardo trees, with sizes given by the
+/// Leonardo numbers, merging the two rightmost trees with each new element
+/// whenever their sizes are consecutive Leonardo numbers and keeping the roots
+/// in increasing order, so the largest element is the last root.  Then shrink
+/// it from right to left, each removed root already in place, splitting its
+/// tree in its two children.  In place, $O(n \log n)$ in the worst case, but
+/// close to $O(n)$ on mostly sorted inputs, where the elements barely move.
+/// Not stable.
+pub fn smooth_sort<T: Ord>(v: &mut [T]) {
+    smooth_sort_by(v, T::cmp);
+}
+
+/// [`smooth_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn smooth_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    // bit `k` set if there is a tree of order `k`
+    let mut orders: u128 = 0;
+    for i in 0..v.len() {
+        let lowest = orders.trailing_zeros();
+        if orders != 0 && orders & (1 << (lowest + 1)) != 0 {
+            // merge the trees of orders `lowest + 1` and `lowest` under `v[i]`
+            orders &= !(3 << lowest);
+            orders |= 1 << (lowest + 2);
+        } else if lowest == 1 {
+            orders |= 1;
+        } else {
+            orders |= 2;
+        }
+
+        // a tree that will become a child of a later root only needs to be a
+        // heap, the roots are put in order once it is final
+        let order = orders.trailing_zeros() as usize;
+        let rest = orders & !(1 << order);
+        let remaining = v.len() - 1 - i;
+        let merged_later = if rest != 0 && rest.trailing_zeros() as usize == order + 1 {
+            remaining > 0
+        } else {
+            remaining > LEONARDO[order.saturating_sub(1)]
+        };
+        if merged_later {
+            leonardo_sift(v, i, order, &mut compare);
+        } else {
+            leonardo_rectify(v, i, orders, &mut compare);
+        }
+    }
+
+    for i in (1..v.len()).rev() {
+        let order = orders.trailing_zeros() as usize;
+        orders &= !(1 << order);
+        if order >= 2 {
+            // the children become trees of their own, and their roots have to
+            // be in order with the previous ones
+            let right = i - 1;
+            let left = right - LEONARDO[order - 2];
+            orders |= 1 << (order - 1);
+            leonardo_rectify(v, left, orders, &mut compare);
+            orders |= 1 << (order - 2);
+            leonardo_rectify(v, right, orders, &mut compare);
+        }
+    }
+}
+
+/// [`smooth_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn smooth_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    smooth_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// Sort only the `k` smallest elements of `v`, into `v[..k]`, leaving the
+/// others in `v[k..]` in no particular order.  The first `k` elements are made
+/// a heap with the largest on top, which each of the others replaces if it is
+/// smaller, and at the end it is sorted as in [`heap_sort`], for
+/// $O(n \log k)$.  A `k` larger than `v.len()` sorts all.
+pub fn partial_sort<T: Ord>(v: &mut [T], k: usize) {
+    partial_sort_by(v, k, T::cmp);
+}
+
+/// [`partial_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn partial_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], k: usize, mut compare: F) {
+    let k = k.min(v.len());
+    if k == 0 {
+        return;
+    }
+
+    let (heap, rest) = v.split_at_mut(k);
+    for i in (0..=k / 2).rev() {
+        sift_down(heap, i, &mut compare);
+    }
+    for x in rest.iter_mut() {
+        if compare(x, &heap[0]) == Ordering::Less {
+            mem::swap(x, &mut heap[0]);
+            sift_down(heap, 0, &mut compare);
+        }
+    }
+
+    for i in (1..k).rev() {
+        swap(heap, 0, i);
+        sift_down(&mut heap[..i], 0, &mut compare);
+    }
+}
+
+/// Sort only the `k` largest elements of `v`, into `v[v.len() - k..]`, leaving
+/// the others before them in no particular order, as for a leaderboard.  Like
+/// [`partial_sort`], with the last `k` elements made a heap with the smallest
+/// on top, which each of the others replaces if it is larger.  With `k` equal
+/// to or larger than `v.len()` the whole of `v` is sorted.
+pub fn partial_sort_largest<T: Ord>(v: &mut [T], k: usize) {
+    let k = k.min(v.len());
+    if k == 0 {
+        return;
+    }
+
+    let mut reversed = |a: &T, b: &T| b.cmp(a);
+    let (rest, heap) = v.split_at_mut(v.len() - k);
+    for i in (0..=k / 2).rev() {
+        sift_down(heap, i, &mut reversed);
+    }
+    for x in rest.iter_mut() {
+        if *x > heap[0] {
+            mem::swap(x, &mut heap[0]);
+            sift_down(heap, 0, &mut reversed);
+        }
+    }
+
+    // extracting the smallest to the end leaves the heap in decreasing order
+    for i in (1..k).rev() {
+        swap(heap, 0, i);
+        sift_down(&mut heap[..i], 0, &mut reversed);
+    }
+    heap.reverse();
+}
+
+/// Swap `v[i]` and `v[j]`, with `i < j`, if they are out of the order given by
+/// `ascending`: the comparator of a sorting network.
+fn compare_exchange<T: Ord>(v: &mut [T], i: usize, j: usize, ascending: bool) {
+    compare_exchange_by(v, i, j, ascending, &mut T::cmp);
+}
+
+/// [`compare_exchange`] ordering elements with `compare`.
+fn compare_exchange_by<T, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    i: usize,
+    j: usize,
+    ascending: bool,
+    compare: &mut F,
+) {
+    let out_of_order = if ascending {
+        Ordering::Greater
+    } else {
+        Ordering::Less
+    };
+    if compare(&v[i], &v[j]) == out_of_order {
+        swap(v, i, j);
+    }
+}
+
+/// Sort `v` with [`intro_sort`], the general purpose sort of this crate that
+/// needs neither random numbers nor memory, except for two and three
+/// elements, which go straight to optimal sorting networks of one and three
+/// comparisons, without the loops and checks of a general algorithm, for code
+/// that sorts many tiny slices.  Not stable.
+pub fn sort<T: Ord>(v: &mut [T]) {
+    match v.len() {
+        0 | 1 => {}
+        2 => compare_exchange(v, 0, 1, true),
+        3 => {
+            compare_exchange(v, 0, 1, true);
+            compare_exchange(v, 1, 2, true);
+            compare_exchange(v, 0, 1, true);
+        }
+        _ => intro_sort(v),
+    }
+}
+
+/// Merge `from[..half]` and `from[half..]` into `to[..from.len()]`, ordering
+/// elements with `compare`.
+#[cfg(feature = "alloc")]
+fn merge<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    from: &[T],
+    half: usize,
+    to: &mut [T],
+    compare: &mut F,
+) {
+    let mut i = 0;
+    let mut j = half;
+    for t in to.iter_mut().take(from.len()) {
+        if i < half && (j >= from.len() || compare(&from[i], &from[j]) != Ordering::Greater) {
+            *t = from[i].clone();
+            i += 1;
+        } else {
+            *t = from[j].clone();
+            j += 1;
+        }
+    }
+}
+
+/// Like [`merge`], for `Copy` elements, copying each run of elements that come
+/// from the same half with a single slice copy.
+#[cfg(feature = "alloc")]
+fn merge_copy<T: Copy, F: FnMut(&T, &T) -> Ordering>(
+    from: &[T],
+    half: usize,
+    to: &mut [T],
+    compare: &mut F,
+) {
+    let n = from.len();
+    let mut i = 0;
+    let mut j = half;
+    let mut k = 0;
+    // whether the next run comes from the left half: the comparison that ends
+    // a run also tells that its first element goes next, so each pair is
+    // compared only once
+    let mut left = 0 < half && half < n && compare(&from[i], &from[j]) != Ordering::Greater;
+    while i < half && j < n {
+        if left {
+            let start = i;
+            i += 1;
+            while i < half && compare(&from[i], &from[j]) != Ordering::Greater {
+                i += 1;
+            }
+            to[k..k + i - start].copy_from_slice(&from[start..i]);
+            k += i - start;
+        } else {
+            let start = j;
+            j += 1;
+            while j < n && compare(&from[j], &from[i]) == Ordering::Less {
+                j += 1;
+            }
+            to[k..k + j - start].copy_from_slice(&from[start..j]);
+            k += j - start;
+        }
+        left = !left;
+    }
+
+    // at most one of the halves still has elements
+    to[k..k + half - i].copy_from_slice(&from[i..half]);
+    to[k + half - i..n].copy_from_slice(&from[j..n]);
+}
+
+/// **Merge sort** by breaking the array in half, recursing, and
+/// following this way *top down*.  Stable: equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_top_down_by(v, T::cmp);
+}
+
+/// [`merge_sort_top_down`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: F) {
+    merge_sort_with_buffer_by(v, &mut vec![], compare);
+}
+
+/// [`merge_sort_top_down`] using `scratch` as its auxiliary memory, cleared and
+/// grown as needed, instead of allocating it, so that sorting many slices in a
+/// loop can reuse a single allocation.  Stable: equal elements keep their
+/// order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_with_buffer<T: Ord + Clone>(v: &mut [T], scratch: &mut Vec<T>) {
+    merge_sort_with_buffer_by(v, scratch, T::cmp);
+}
+
+/// [`merge_sort_with_buffer`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_with_buffer_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    scratch: &mut Vec<T>,
+    mut compare: F,
+) {
+    // compute the recursive merge sort of `w` and store the result into `v`
+    fn split_merge<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+        w: &mut [T],
+        v: &mut [T],
+        compare: &mut F,
+    ) {
+        if w.len() > 1 {
+            let half = w.len() / 2;
+            split_merge(&mut v[..half], &mut w[..half], compare);
+            split_merge(&mut v[half..], &mut w[half..], compare);
+            merge(w, half, v, compare);
+        }
+    }
+
+    scratch.clear();
+    scratch.extend_from_slice(v);
+    split_merge(scratch, v, &mut compare);
+}
+
+/// [`merge_sort_top_down`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_by_key<T: Clone, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    merge_sort_top_down_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`merge_sort_top_down`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_cached_key`]: keys are computed once per element, and
+/// then the indices of the elements sorted by them, ties broken by index, so
+/// the order of equal keys is kept.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_by_cached_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    let keys: Vec<K> = v.iter().map(f).collect();
+    let mut perm: Vec<usize> = (0..v.len()).collect();
+    merge_sort_top_down_by(&mut perm, |&a, &b| keys[a].cmp(&keys[b]).then(a.cmp(&b)));
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// [`merge_sort_top_down`] in decreasing order.  Stable: equal elements keep
+/// their order, unlike sorting in increasing order and reversing.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_desc<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_top_down_by(v, |a, b| b.cmp(a));
+}
+
+/// **Merge sort** top down, for `Copy` elements, merging runs of elements
+/// with slice copies instead of element by element.  Stable: equal elements
+/// keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_copy<T: Ord + Copy>(v: &mut [T]) {
+    merge_sort_copy_by(v, T::cmp);
+}
+
+/// [`merge_sort_copy`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_copy_by<T: Copy, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    fn split_merge<T: Copy, F: FnMut(&T, &T) -> Ordering>(
+        w: &mut [T],
+        v: &mut [T],
+        compare: &mut F,
+    ) {
+        if w.len() > 1 {
+            let half = w.len() / 2;
+            split_merge(&mut v[..half], &mut w[..half], compare);
+            split_merge(&mut v[half..], &mut w[half..], compare);
+            merge_copy(w, half, v, compare);
+        }
+    }
+
+    let mut w: Vec<_> = v.to_vec();
+    split_merge(&mut w, v, &mut compare);
+}
+
+/// Merge the sorted `left` and `right` into a new vector, moving the elements
+/// instead of cloning them, the ones of `left` first when equal.
+#[cfg(feature = "alloc")]
+fn merge_owned<T: Ord>(left: Vec<T>, right: Vec<T>) -> Vec<T> {
+    let mut to = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        let from = if b < a { &mut right } else { &mut left };
+        to.extend(from.next());
+    }
+    to.extend(left);
+    to.extend(right);
+    to
+}
+
+/// **Merge sort** top down of owned elements, splitting `v` into vectors for
+/// each half and merging them into a new one by moving the elements, so that,
+/// unlike the other merge sorts, it needs no `Clone` and clones nothing, which
+/// pays off for elements like `String` that are expensive to clone.  Small
+/// halves are insertion sorted.  Stable: equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_owned<T: Ord>(v: &mut Vec<T>) {
+    fn sort<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+        if v.len() <= 16 {
+            insertion_sort(&mut v);
+            return v;
+        }
+        let right = sort(v.split_off(v.len() / 2));
+        merge_owned(sort(v), right)
+    }
+
+    *v = sort(mem::take(v));
+}
+
+/// **Merge sort** top down, using insertion sort for small sub arrays.  Stable:
+/// equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_insert<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_top_down_insert_by(v, T::cmp);
+}
+
+/// [`merge_sort_top_down_insert`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_insert_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut compare: F,
+) {
+    fn split_merge<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+        w: &mut [T],
+        v: &mut [T],
+        compare: &mut F,
+    ) {
+        if w.len() <= 16 {
+            insertion_sort_by(v, compare);
+        } else {
+            let half = w.len() / 2;
+            split_merge(&mut v[..half], &mut w[..half], compare);
+            split_merge(&mut v[half..], &mut w[half..], compare);
+            merge(w, half, v, compare);
+        }
+    }
+
+    let mut w: Vec<_> = v.to_vec();
+    split_merge(&mut w, v, &mut compare);
+}
+
+/// **Merge sort** top down, skipping the merge when the two sorted halves are
+/// already in order (the last of the left is not larger than the first of the
+/// right), which makes sorted and partially sorted inputs much faster.  Stable:
+/// equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_adaptive<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_top_down_adaptive_by(v, T::cmp);
+}
+
+/// [`merge_sort_top_down_adaptive`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_top_down_adaptive_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut compare: F,
+) {
+    fn split_merge<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+        w: &mut [T],
+        v: &mut [T],
+        compare: &mut F,
+    ) {
+        if w.len() > 1 {
+            let half = w.len() / 2;
+            split_merge(&mut v[..half], &mut w[..half], compare);
+            split_merge(&mut v[half..], &mut w[half..], compare);
+            if compare(&w[half - 1], &w[half]) != Ordering::Greater {
+                v.clone_from_slice(w);
+            } else {
+                merge(w, half, v, compare);
+            }
+        }
+    }
+
+    let mut w: Vec<_> = v.to_vec();
+    split_merge(&mut w, v, &mut compare);
+}
+
+/// **Merge sort** by merging pairs, then four elements, so forth,
+/// doubling, going *bottom up* until finally both halves of the
+/// array are merged in the whole.  Stable: equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_bottom_up_by(v, T::cmp);
+}
+
+/// [`merge_sort_bottom_up`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut compare: F,
+) {
+    let mut w: Vec<_> = v.to_vec();
+
+    let n = v.len();
+    let mut v_to_w = true;
+
+    let mut width = 1;
+    while width < n {
+        let mut i = 0;
+        while i < n {
+            let end = (i + 2 * width).min(n);
+            if v_to_w {
+                merge(&v[i..end], width, &mut w[i..end], &mut compare);
+            } else {
+                merge(&w[i..end], width, &mut v[i..end], &mut compare);
+            }
+            i += 2 * width;
+        }
+
+        v_to_w = !v_to_w;
+        width *= 2;
+    }
+
+    if !v_to_w {
+        v.clone_from_slice(&w);
+    }
+}
+
+/// [`merge_sort_bottom_up`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_by_key<T: Clone, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    merge_sort_bottom_up_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// [`merge_sort_bottom_up`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_cached_key`]: keys are computed once per element, and
+/// then the indices of the elements sorted by them, ties broken by index, so
+/// the order of equal keys is kept.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_by_cached_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], f: F) {
+    let keys: Vec<K> = v.iter().map(f).collect();
+    let mut perm: Vec<usize> = (0..v.len()).collect();
+    merge_sort_bottom_up_by(&mut perm, |&a, &b| keys[a].cmp(&keys[b]).then(a.cmp(&b)));
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// [`merge_sort_bottom_up`] in decreasing order.  Stable, as
+/// [`merge_sort_top_down_desc`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_desc<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_bottom_up_by(v, |a, b| b.cmp(a));
+}
+
+/// **Merge sort** *bottom up*, like [`merge_sort_bottom_up`], calling
+/// `on_progress` at the end of each merging pass with the completed fraction
+/// of the work, in `0.0..=1.0`.  The reported values never decrease and the
+/// last one is always `1.0`, even when there is nothing to merge.  Stable:
+/// equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_with_progress<T: Ord + Clone, F: FnMut(f32)>(v: &mut [T], mut on_progress: F) {
+    let mut w: Vec<_> = v.to_vec();
+
+    let n = v.len();
+    let mut v_to_w = true;
+
+    let mut width = 1;
+    while width < n {
+        let mut i = 0;
+        while i < n {
+            let end = (i + 2 * width).min(n);
+            if v_to_w {
+                merge(&v[i..end], width, &mut w[i..end], &mut T::cmp);
+            } else {
+                merge(&w[i..end], width, &mut v[i..end], &mut T::cmp);
+            }
+            i += 2 * width;
+        }
+
+        v_to_w = !v_to_w;
+        width *= 2;
+
+        // runs of `width` elements are now sorted
+        if width < n {
+            on_progress(width as f32 / n as f32);
+        }
+    }
+
+    if !v_to_w {
+        v.clone_from_slice(&w);
+    }
+
+    on_progress(1.0);
+}
+
+/// **Merge sort** bottom up, using insertion sort for small sub arrays.
+/// Stable: equal elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_insert<T: Ord + Clone>(v: &mut [T]) {
+    merge_sort_bottom_up_insert_by(v, T::cmp);
+}
+
+/// [`merge_sort_bottom_up_insert`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn merge_sort_bottom_up_insert_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+    v: &mut [T],
+    mut compare: F,
+) {
+    let mut w: Vec<_> = v.to_vec();
+
+    let n = v.len();
+
+    // swap between v and w to avoid too much cloning
+    let mut v_to_w = true;
+
+    // the initial value defines the sizes of the initial insertion sort
+    let mut width = 8;
+
+    // a first round of insertion sort...
+    for i in (0..n).step_by(2 * width) {
+        let end = (i + 2 * width).min(n);
+        insertion_sort_by(&mut v[i..end], &mut compare);
+    }
+
+    // ...then increasing merging groups, until all array is merge
+    while width < n {
+        for i in (0..n).step_by(2 * width) {
+            let end = (i + 2 * width).min(n);
+            if v_to_w {
+                merge(&v[i..end], width, &mut w[i..end], &mut compare);
+            } else {
+                merge(&w[i..end], width, &mut v[i..end], &mut compare);
+            }
+        }
+        v_to_w = !v_to_w;
+        width *= 2;
+    }
+
+    // last clone if final iteration left the sorted result in w
+    if !v_to_w {
+        v.clone_from_slice(&w);
+    }
+}
+
+/// Size of the L1 data cache that [`merge_sort_blocked`] sizes its blocks for,
+/// 32 KiB on most current processors.
+#[cfg(feature = "alloc")]
+const L1_CACHE_BYTES: usize = 32 * 1024;
+
+/// **Merge sort** bottom up, blocked for the cache: `v` is split in blocks
+/// small enough that a block and its auxiliary memory fit together in the L1
+/// cache, each block is sorted completely, by insertion sorting runs of 16
+/// elements and merging them bottom up, while it is in the cache, and only then
+/// the blocks are merged bottom up.  The same merges as
+/// [`merge_sort_bottom_up_insert`], but the first passes run over each block
+/// instead of streaming through the whole array.  Stable: equal elements keep
+/// their order.
+///
+/// In practice it is only slightly faster than [`merge_sort_bottom_up_insert`],
+/// whose sequential passes the hardware prefetcher already serves well.
+#[cfg(feature = "alloc")]
+pub fn merge_sort_blocked<T: Ord + Clone>(v: &mut [T]) {
+    // merge the sorted runs of `width` elements of `v` bottom up, with `w` as
+    // auxiliary memory, leaving the result in `v`
+    fn merge_passes<T: Ord + Clone>(v: &mut [T], w: &mut [T], mut width: usize) {
+        let n = v.len();
+        let mut v_to_w = true;
+        while width < n {
+            for i in (0..n).step_by(2 * width) {
+                let end = (i + 2 * width).min(n);
+                if v_to_w {
+                    merge(&v[i..end], width, &mut w[i..end], &mut T::cmp);
+                } else {
+                    merge(&w[i..end], width, &mut v[i..end], &mut T::cmp);
+                }
+            }
+            v_to_w = !v_to_w;
+            width *= 2;
+        }
+        if !v_to_w {
+            v.clone_from_slice(w);
+        }
+    }
+
+    let n = v.len();
+    let block = (L1_CACHE_BYTES / 2 / mem::size_of::<T>().max(1)).max(16);
+    let mut w: Vec<_> = v.to_vec();
+    for i in (0..n).step_by(block) {
+        let end = (i + block).min(n);
+        for j in (i..end).step_by(16) {
+            insertion_sort(&mut v[j..(j + 16).min(end)]);
+        }
+        merge_passes(&mut v[i..end], &mut w[i..end], 16);
+    }
+    merge_passes(v, &mut w, block);
+}
+
+/// Number of consecutive elements taken from the same run after which
+/// [`tim_sort`] starts galloping, copying blocks found by exponential search.
+#[cfg(feature = "alloc")]
+const MIN_GALLOP: usize = 7;
+
+/// Number of leading elements of `v` for which `pred` holds, where `pred` holds
+/// for a prefix of `v` and not after it, found by exponential search from the
+/// start, which is faster than a binary search when the prefix is short.
+#[cfg(feature = "alloc")]
+fn gallop<T, P: FnMut(&T) -> bool>(v: &[T], mut pred: P) -> usize {
+    let mut bound = 1;
+    while bound <= v.len() && pred(&v[bound - 1]) {
+        bound *= 2;
+    }
+    let lo = bound / 2;
+    lo + v[lo..bound.min(v.len())].partition_point(pred)
+}
+
+/// **Timsort**: split `v` into its natural runs, ascending or strictly
+/// descending, reversing the descending ones, with short runs extended to a
+/// minimum length by insertion sort.  Runs are pushed on a stack and merged
+/// while their lengths do not shrink fast enough, so merges stay balanced, and
+/// each merge skips the elements already in place and gallops over long
+/// stretches taken from the same run.  Stable, and close to $O(n)$ on sorted,
+/// reversed or mostly sorted inputs, with a buffer the size of the left runs.
+#[cfg(feature = "alloc")]
+pub fn tim_sort<T: Ord + Clone>(v: &mut [T]) {
+    tim_sort_by(v, T::cmp);
+}
+
+/// [`tim_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn tim_sort_by<T: Clone, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    // between 32 and 64, so that `n / min_run` is a power of two or just below
+    fn min_run_length(mut n: usize) -> usize {
+        let mut r = 0;
+        while n >= 64 {
+            r |= n & 1;
+            n >>= 1;
+        }
+        n + r
+    }
+
+    // merge the sorted runs `v[..mid]` and `v[mid..]`
+    fn merge_gallop<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+        v: &mut [T],
+        mid: usize,
+        buf: &mut Vec<T>,
+        compare: &mut F,
+    ) {
+        // the elements of the left run not larger than the first of the right
+        // run, and those of the right run smaller than the last of the left,
+        // are already in place
+        let start = gallop(&v[..mid], |x| compare(x, &v[mid]) != Ordering::Greater);
+        let end = mid + gallop(&v[mid..], |x| compare(x, &v[mid - 1]) == Ordering::Less);
+        let v = &mut v[start..end];
+        let mid = mid - start;
+
+        buf.clear();
+        buf.extend_from_slice(&v[..mid]);
+        let mut a = 0;
+        let mut b = mid;
+        let mut dest = 0;
+        let mut wins_a = 0;
+        let mut wins_b = 0;
+        while a < buf.len() && b < v.len() {
+            if compare(&v[b], &buf[a]) == Ordering::Less {
+                v[dest] = v[b].clone();
+                b += 1;
+                wins_a = 0;
+                wins_b += 1;
+            } else {
+                v[dest] = buf[a].clone();
+                a += 1;
+                wins_a += 1;
+                wins_b = 0;
+            }
+            dest += 1;
+
+            if wins_a >= MIN_GALLOP || wins_b >= MIN_GALLOP {
+                if b < v.len() {
+                    let k = gallop(&buf[a..], |x| compare(x, &v[b]) != Ordering::Greater);
+                    v[dest..dest + k].clone_from_slice(&buf[a..a + k]);
+                    a += k;
+                    dest += k;
+                }
+                if a < buf.len() {
+                    let k = gallop(&v[b..], |x| compare(x, &buf[a]) == Ordering::Less);
+                    for i in 0..k {
+                        v[dest + i] = v[b + i].clone();
+                    }
+                    b += k;
+                    dest += k;
+                }
+                wins_a = 0;
+                wins_b = 0;
+            }
+        }
+
+        // what is left of the right run is already in place
+        v[dest..dest + buf.len() - a].clone_from_slice(&buf[a..]);
+    }
+
+    // merge the runs at `i` and `i + 1` of the stack of `(start, len)`
+    fn merge_at<T: Clone, F: FnMut(&T, &T) -> Ordering>(
+        v: &mut [T],
+        runs: &mut Vec<(usize, usize)>,
+        i: usize,
+        buf: &mut Vec<T>,
+        compare: &mut F,
+    ) {
+        let (start, len) = runs[i];
+        let (_, next_len) = runs.remove(i + 1);
+        merge_gallop(&mut v[start..start + len + next_len], len, buf, compare);
+        runs[i].1 += next_len;
+    }
+
+    let n = v.len();
+    let min_run = min_run_length(n);
+    let mut runs: Vec<(usize, usize)> = vec![];
+    let mut buf = vec![];
+
+    let mut start = 0;
+    while start < n {
+        let mut end = start + 1;
+        if end < n && compare(&v[end], &v[start]) == Ordering::Less {
+            // strictly descending, so reversing keeps equal elements in order
+            while end < n && compare(&v[end], &v[end - 1]) == Ordering::Less {
+                end += 1;
+            }
+            v[start..end].reverse();
+        } else {
+            while end < n && compare(&v[end], &v[end - 1]) != Ordering::Less {
+                end += 1;
+            }
+        }
+
+        let forced = (start + min_run).min(n);
+        if end < forced {
+            insertion_sort_by(&mut v[start..forced], &mut compare);
+            end = forced;
+        }
+        runs.push((start, end - start));
+        start = end;
+
+        // keep each run longer than the next one, and than the two next ones
+        // together, merging the shorter neighbor of the run below the top
+        while runs.len() > 1 {
+            let k = runs.len();
+            let len = |i: usize| runs[i].1;
+            if (k >= 3 && len(k - 3) <= len(k - 2) + len(k - 1))
+                || (k >= 4 && len(k - 4) <= len(k - 3) + len(k - 2))
+            {
+                let i = if len(k - 3) < len(k - 1) {
+                    k - 3
+                } else {
+                    k - 2
+                };
+                merge_at(v, &mut runs, i, &mut buf, &mut compare);
+            } else if len(k - 2) <= len(k - 1) {
+                merge_at(v, &mut runs, k - 2, &mut buf, &mut compare);
+            } else {
+                break;
+            }
+        }
+    }
+
+    while runs.len() > 1 {
+        let i = runs.len() - 2;
+        merge_at(v, &mut runs, i, &mut buf, &mut compare);
+    }
+}
+
+/// [`tim_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+#[cfg(feature = "alloc")]
+pub fn tim_sort_by_key<T: Clone, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    tim_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **LSD radix sort** of `usize` keys, one byte per pass, from the least to
+/// the most significant byte, stopping once the remaining bytes of the largest
+/// key are all zero.  Not a comparison sort: it only applies to integer keys,
+/// but runs in $O(n)$ passes with $O(n)$ auxiliary memory.  Stable: equal
+/// elements keep their order.
+#[cfg(feature = "alloc")]
+pub fn radix_sort(v: &mut [usize]) {
+    // stable counting sort of `from` into `to` by the byte at `shift`
+    fn pass(from: &[usize], to: &mut [usize], shift: u32) {
+        let mut offsets = [0; 256];
+        for &x in from {
+            offsets[(x >> shift) & 0xff] += 1;
+        }
+
+        let mut sum = 0;
+        for offset in offsets.iter_mut() {
+            let count = *offset;
+            *offset = sum;
+            sum += count;
+        }
+
+        for &x in from {
+            let digit = (x >> shift) & 0xff;
+            to[offsets[digit]] = x;
+            offsets[digit] += 1;
+        }
+    }
+
+    let max = match v.iter().max() {
+        Some(&max) => max,
+        None => return,
+    };
+
+    let mut w = vec![0; v.len()];
+    let mut v_to_w = true;
+
+    let mut shift = 0;
+    while shift < usize::BITS && max >> shift > 0 {
+        if v_to_w {
+            pass(v, &mut w, shift);
+        } else {
+            pass(&w, v, shift);
+        }
+        v_to_w = !v_to_w;
+        shift += 8;
+    }
+
+    if !v_to_w {
+        v.copy_from_slice(&w);
+    }
+}
+
+/// The native [`slice::sort`], for comparison.  Stable: equal elements keep
+/// their order.
+#[cfg(feature = "alloc")]
+pub fn native_sort<T: Ord>(v: &mut [T]) {
+    v.sort();
+}
+
+/// The native [`slice::sort_unstable`], for comparison.  Not stable.
+pub fn native_unstable_sort<T: Ord>(v: &mut [T]) {
     v.sort_unstable();
 }
+
+/// Sort `v` in decreasing order: the `*_desc` version of [`sort`], as
+/// [`heap_sort_desc`] is of [`heap_sort`], running [`intro_sort_by`] with the
+/// comparison reversed.  The same order as sorting the elements wrapped in
+/// [`core::cmp::Reverse`], but without wrapping and unwrapping them.  Not
+/// stable.
+pub fn sort_reverse<T: Ord>(v: &mut [T]) {
+    intro_sort_by(v, |a, b| b.cmp(a));
+}
+
+/// Reorder `v` so that `v[i]` receives the element previously at `v[perm[i]]`,
+/// following each cycle of the permutation and swapping elements into place.
+/// Runs in $O(n)$ time with no auxiliary memory.  Returns the number of swaps,
+/// `n - c` for a permutation of `c` cycles, counting elements already in place
+/// as cycles of their own.
+///
+/// `perm` is used as scratch space to mark visited positions: on return it is
+/// left as the identity permutation and its original contents are lost.
+///
+/// Panics if `perm` is not a permutation of `0..v.len()`.
+pub fn apply_permutation_in_place<T>(v: &mut [T], perm: &mut [usize]) -> usize {
+    assert_eq!(
+        v.len(),
+        perm.len(),
+        "`perm` must have the same length as `v`"
+    );
+    let mut swaps = 0;
+    for i in 0..v.len() {
+        // walk the cycle starting at `i`, pulling each element into place and
+        // marking its position as visited by making it a fixed point
+        let mut cur = i;
+        while perm[cur] != i {
+            let next = perm[cur];
+            assert!(perm[next] != next, "`perm` is not a permutation");
+            swap(v, cur, next);
+            swaps += 1;
+            perm[cur] = cur;
+            cur = next;
+        }
+        perm[cur] = cur;
+    }
+    swaps
+}
+
+/// Indices of `v` in the order that would sort it, so that `v[idx[0]]`,
+/// `v[idx[1]]`, ... are in increasing order, leaving `v` untouched, to reorder
+/// other slices the same way.  Stable: equal elements keep the order of their
+/// indices.  The indices are sorted with [`merge_sort_copy_by`], comparing the
+/// elements they point to.
+#[cfg(feature = "alloc")]
+pub fn argsort<T: Ord>(v: &[T]) -> Vec<usize> {
+    argsort_by(v, T::cmp)
+}
+
+/// [`argsort`] ordering elements with `compare`, like [`slice::sort_by`].
+#[cfg(feature = "alloc")]
+pub fn argsort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &[T], mut compare: F) -> Vec<usize> {
+    let mut idx: Vec<usize> = (0..v.len()).collect();
+    merge_sort_copy_by(&mut idx, |&a, &b| compare(&v[a], &v[b]));
+    idx
+}
+
+/// Assign ranks to the sorted order given by `idx`.  A run of equal elements
+/// shares the rank of its first element; `dense` makes the next distinct
+/// element take the following rank instead of skipping over the run.
+#[cfg(feature = "alloc")]
+fn rank_indices<T: Ord>(v: &[T], idx: &[usize], dense: bool) -> Vec<usize> {
+    let mut ranks = vec![0; v.len()];
+    let mut current = 0;
+    for k in 1..idx.len() {
+        if v[idx[k]] != v[idx[k - 1]] {
+            current = if dense { current + 1 } else { k };
+        }
+        ranks[idx[k]] = current;
+    }
+    ranks
+}
+
+/// For each position of `v`, the 0-based rank of its element among all
+/// elements of `v`.  Equal elements share the smallest rank and the following
+/// ranks are skipped ("competition" or "1224" ranking), so `[30, 10, 20, 20]`
+/// ranks as `[3, 0, 1, 1]`.
+#[cfg(feature = "alloc")]
+pub fn rank<T: Ord>(v: &[T]) -> Vec<usize> {
+    rank_indices(v, &argsort(v), false)
+}
+
+/// Like [`rank`], but the ranks following a tie are not skipped
+/// ("dense" or "1223" ranking), so `[30, 10, 20, 20]` ranks as `[2, 0, 1, 1]`.
+#[cfg(feature = "alloc")]
+pub fn dense_rank<T: Ord>(v: &[T]) -> Vec<usize> {
+    rank_indices(v, &argsort(v), true)
+}
+
+/// Sort `v` with few swaps: the sorted order is first computed over indices,
+/// comparing elements where they lie, and then applied with
+/// [`apply_permutation_in_place`].  Returns the number of swaps, exactly
+/// `n - c`, where `c` is the number of cycles in the sorting permutation:
+/// minimal for the stable target permutation, but optimal only when elements
+/// are distinct, as equal elements could trade places to close cycles sooner.
+/// It pays off when elements are expensive to move.  Each swap moves three
+/// elements through a temporary; see [`sort_min_writes`] to write each element
+/// only once, for elements that can be cloned.  Stable, but uses $O(n)$
+/// auxiliary memory for the indices.
+#[cfg(feature = "alloc")]
+pub fn sort_minimize_moves<T: Ord>(v: &mut [T]) -> usize {
+    let mut perm = argsort(v);
+    apply_permutation_in_place(v, &mut perm)
+}
+
+/// Return whether `v` is in increasing order, each element not larger than the
+/// next.  Empty and single element slices are sorted.
+pub fn is_sorted<T: Ord>(v: &[T]) -> bool {
+    is_sorted_by(v, T::cmp)
+}
+
+/// [`is_sorted`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn is_sorted_by<T, F: FnMut(&T, &T) -> Ordering>(v: &[T], mut compare: F) -> bool {
+    v.windows(2)
+        .all(|w| compare(&w[0], &w[1]) != Ordering::Greater)
+}
+
+/// Sort `v` only if it is not already sorted, returning whether it had to be.
+/// Trusted sorted input costs a single $O(n)$ check, while anything else is
+/// fixed with [`heap_sort`].
+pub fn ensure_sorted<T: Ord>(v: &mut [T]) -> bool {
+    if is_sorted(v) {
+        false
+    } else {
+        heap_sort(v);
+        true
+    }
+}
+
+/// Sort sequences lexicographically, *most significant element first*: group
+/// the sequences by their first element, then sort each group on the
+/// remaining elements, with insertion sort for small groups.  Shorter
+/// sequences sort before their extensions, so empty ones come first.  Only the
+/// smaller groups are sorted recursively, and the largest in a loop, so the
+/// recursion stays logarithmic however long the prefixes the sequences share.
+#[cfg(feature = "alloc")]
+pub fn sort_sequences<T: Ord>(v: &mut [Vec<T>]) {
+    // sort `v`, where all sequences are known to share their first `depth` elements
+    fn msd<T: Ord>(mut v: &mut [Vec<T>], mut depth: usize) {
+        while v.len() > 16 {
+            // sequences that end at `depth` are prefixes of all others
+            let mut ended = 0;
+            for i in 0..v.len() {
+                if v[i].len() == depth {
+                    swap(v, ended, i);
+                    ended += 1;
+                }
+            }
+
+            let rest = v;
+            v = &mut rest[ended..];
+            v.sort_unstable_by(|a, b| a[depth].cmp(&b[depth]));
+
+            // groups sharing the element at `depth`, as `start..end`
+            let group_end = |v: &[Vec<T>], start: usize| {
+                let mut end = start + 1;
+                while end < v.len() && v[end][depth] == v[start][depth] {
+                    end += 1;
+                }
+                end
+            };
+
+            let mut largest = 0..0;
+            let mut start = 0;
+            while start < v.len() {
+                let end = group_end(v, start);
+                if end - start > largest.len() {
+                    largest = start..end;
+                }
+                start = end;
+            }
+
+            start = 0;
+            while start < v.len() {
+                let end = group_end(v, start);
+                if start != largest.start {
+                    msd(&mut v[start..end], depth + 1);
+                }
+                start = end;
+            }
+
+            let rest = v;
+            v = &mut rest[largest];
+            depth += 1;
+        }
+        insertion_sort(v);
+    }
+
+    msd(v, 0);
+}
+
+/// **MSD radix sort** of byte strings, in lexicographic order: distribute the
+/// strings into buckets by their first byte, with [`counting_sort_by_key`],
+/// then sort each bucket on the next byte, with insertion sort for small
+/// buckets.  The strings that end before the byte go to a bucket of their own,
+/// ahead of all others, so prefixes sort before their extensions.  Only the
+/// smaller buckets are sorted recursively, and the largest in a loop, so the
+/// recursion stays logarithmic however long the prefixes the strings share.
+#[cfg(feature = "alloc")]
+pub fn radix_sort_bytes(v: &mut [Vec<u8>]) {
+    // sort `v`, where all strings are known to share their first `depth` bytes
+    fn msd(mut v: &mut [Vec<u8>], mut depth: usize) {
+        while v.len() > 16 {
+            // bucket `0` holds the strings that end at `depth`
+            let bucket = |s: &Vec<u8>| s.get(depth).map_or(0, |&b| b as usize + 1);
+            let mut counts = [0; 257];
+            for s in v.iter() {
+                counts[bucket(s)] += 1;
+            }
+            counting_sort_by_key(v, 256, bucket);
+
+            let largest = (1..257).max_by_key(|&b| counts[b]).unwrap();
+            let mut largest_start = 0;
+            let mut start = counts[0];
+            for b in 1..257 {
+                if b == largest {
+                    largest_start = start;
+                } else {
+                    msd(&mut v[start..start + counts[b]], depth + 1);
+                }
+                start += counts[b];
+            }
+
+            let rest = v;
+            v = &mut rest[largest_start..largest_start + counts[largest]];
+            depth += 1;
+        }
+        insertion_sort(v);
+    }
+
+    msd(v, 0);
+}
+
+/// **Counting sort** of small integers, in `0..=max`: count the occurrences of
+/// each value and rewrite `v` with each value repeated as many times as it was
+/// counted, in $O(n + max)$.  See [`counting_sort_by_key`] to sort other
+/// elements by an integer key.
+///
+/// Panics, leaving `v` untouched, if any element is larger than `max`.
+#[cfg(feature = "alloc")]
+pub fn counting_sort(v: &mut [u32], max: u32) {
+    let mut counts = vec![0; max as usize + 1];
+    for &x in v.iter() {
+        assert!(x <= max, "element {x} is larger than max {max}");
+        counts[x as usize] += 1;
+    }
+
+    let mut start = 0;
+    for (x, &count) in counts.iter().enumerate() {
+        v[start..start + count].fill(x as u32);
+        start += count;
+    }
+}
+
+/// **Counting sort** of `v` by the key of each element, in `0..=max`: count
+/// the elements of each key, turn the counts into the offset of each key in
+/// the result, and move the elements to their offsets.  Stable, and runs in
+/// $O(n + max)$, with the moves done by [`apply_permutation_in_place`] so `T`
+/// does not need to be `Clone`.
+///
+/// Panics if any key is larger than `max`.
+#[cfg(feature = "alloc")]
+pub fn counting_sort_by_key<T, F: Fn(&T) -> usize>(v: &mut [T], max: usize, key: F) {
+    let mut offsets = vec![0; max + 1];
+    for x in v.iter() {
+        let k = key(x);
+        assert!(k <= max, "key {k} is larger than max {max}");
+        offsets[k] += 1;
+    }
+
+    let mut sum = 0;
+    for offset in offsets.iter_mut() {
+        let count = *offset;
+        *offset = sum;
+        sum += count;
+    }
+
+    // `perm[i]` is the index of the element that goes to position `i`
+    let mut perm = vec![0; v.len()];
+    for (i, x) in v.iter().enumerate() {
+        let k = key(x);
+        perm[offsets[k]] = i;
+        offsets[k] += 1;
+    }
+
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// What [`sort_by_order`] does with an element whose key is not in the order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownKeys {
+    /// Sort the element after all the known ones, keeping its order.
+    Last,
+    /// Fail with an [`UnknownKey`] error, leaving `v` untouched.
+    Error,
+}
+
+/// An element whose key is not in the order given to [`sort_by_order`], by its
+/// index in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownKey {
+    pub index: usize,
+}
+
+impl Display for UnknownKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "key of element {} is not in the order", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for UnknownKey {}
+
+/// Sort `v` by a categorical key, such as a day of the week or a priority
+/// label, in the order the keys appear in `order`: a counting sort over the
+/// positions of the keys in `order`, in $O(n + k)$ for `k` keys.  Elements
+/// with keys not in `order` sort last or fail, as `unknown` says.  Stable:
+/// equal elements keep their order.
+#[cfg(feature = "std")]
+pub fn sort_by_order<T, K: Eq + Hash, F: Fn(&T) -> K>(
+    v: &mut [T],
+    key: F,
+    order: &[K],
+    unknown: UnknownKeys,
+) -> Result<(), UnknownKey> {
+    let positions: HashMap<&K, usize> = order.iter().enumerate().map(|(i, k)| (k, i)).collect();
+    let mut ranks = Vec::with_capacity(v.len());
+    for (index, x) in v.iter().enumerate() {
+        match positions.get(&key(x)) {
+            Some(&rank) => ranks.push(rank),
+            None if unknown == UnknownKeys::Last => ranks.push(order.len()),
+            None => return Err(UnknownKey { index }),
+        }
+    }
+
+    let mut offsets = vec![0; order.len() + 1];
+    for &rank in ranks.iter() {
+        offsets[rank] += 1;
+    }
+    let mut sum = 0;
+    for offset in offsets.iter_mut() {
+        let count = *offset;
+        *offset = sum;
+        sum += count;
+    }
+
+    // `perm[i]` is the index of the element that goes to position `i`
+    let mut perm = vec![0; v.len()];
+    for (i, &rank) in ranks.iter().enumerate() {
+        perm[offsets[rank]] = i;
+        offsets[rank] += 1;
+    }
+
+    apply_permutation_in_place(v, &mut perm);
+    Ok(())
+}
+
+/// **Pigeonhole sort** of `v` by the key of each element: find the smallest and
+/// largest keys, make a hole for each key between them, put each element, by
+/// index, into the hole of its key, and concatenate the holes.  Stable, and
+/// like [`counting_sort_by_key`] moves elements with
+/// [`apply_permutation_in_place`], so elements can carry any payload.  Keys
+/// are computed once per element.
+///
+/// Runs in $O(n + r)$ time and memory for a range of `r` keys between the
+/// smallest and the largest, whether they are used or not: a sparse range,
+/// such as a few keys spread over millions of values, allocates millions of
+/// empty holes.
+#[cfg(feature = "alloc")]
+pub fn pigeonhole_sort<T, K: Into<usize>, F: Fn(&T) -> K>(v: &mut [T], key: F) {
+    let keys: Vec<usize> = v.iter().map(|x| key(x).into()).collect();
+    let (min, max) = match (keys.iter().min(), keys.iter().max()) {
+        (Some(&min), Some(&max)) => (min, max),
+        _ => return,
+    };
+
+    let mut holes: Vec<Vec<usize>> = vec![vec![]; max - min + 1];
+    for (i, &k) in keys.iter().enumerate() {
+        holes[k - min].push(i);
+    }
+
+    let mut perm: Vec<usize> = holes.into_iter().flatten().collect();
+    apply_permutation_in_place(v, &mut perm);
+}
+
+/// **Bucket sort** of floats, meant for values spread uniformly over
+/// `0.0..1.0`: scatter the `n` values into `n` buckets by value, insertion sort
+/// each bucket, and concatenate them, for an average of $O(n)$.  Values out of
+/// that range still sort correctly, but pile up on the first or the last
+/// bucket, which is slow.  Values are ordered as by [`f64::total_cmp`], so
+/// `-0.0` comes before `0.0`, and NaNs, which have no order, are all moved to
+/// the end, in the order they were found.
+#[cfg(feature = "alloc")]
+pub fn bucket_sort_f64(v: &mut [f64]) {
+    let n = v.len();
+    let mut buckets: Vec<Vec<f64>> = vec![vec![]; n];
+    let mut nans = vec![];
+    for &x in v.iter() {
+        if x.is_nan() {
+            nans.push(x);
+        } else {
+            // `as` saturates, so negative values go to the first bucket
+            let b = ((x * n as f64) as usize).min(n - 1);
+            buckets[b].push(x);
+        }
+    }
+
+    let mut start = 0;
+    for mut bucket in buckets {
+        insertion_sort_by(&mut bucket, f64::total_cmp);
+        v[start..start + bucket.len()].copy_from_slice(&bucket);
+        start += bucket.len();
+    }
+    v[start..].copy_from_slice(&nans);
+}
+
+/// Sort the concatenation of `a` and `b`, as with data wrapping around a ring
+/// buffer, so that reading `a` then `b` gives all elements in order.  The
+/// elements are sorted in a temporary buffer and distributed back.
+#[cfg(feature = "alloc")]
+pub fn sort_split<T: Ord + Clone>(a: &mut [T], b: &mut [T]) {
+    let mut w: Vec<_> = a.iter().chain(b.iter()).cloned().collect();
+    merge_sort_top_down_insert(&mut w);
+
+    let (left, right) = w.split_at(a.len());
+    a.clone_from_slice(left);
+    b.clone_from_slice(right);
+}
+
+/// An operation on positions of a slice, as recorded by [`plan_sort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// Compare the elements at both positions.
+    Compare(usize, usize),
+    /// Swap the elements at both positions.
+    Swap(usize, usize),
+    /// Remove the element at the first position and insert it at the second,
+    /// shifting the elements in between.
+    Move(usize, usize),
+}
+
+/// Record the operations the named `algorithm` does to sort `v`, without
+/// touching `v`: the algorithm runs over a vector of indices into `v`.
+/// Supports `"bubble"`, `"insertion"`, `"selection"` and `"heap"`, following
+/// [`bubble_sort`], [`insertion_sort`], [`selection_sort`] and [`heap_sort`].
+///
+/// Panics if `algorithm` is not supported.
+#[cfg(feature = "alloc")]
+pub fn plan_sort<T: Ord>(v: &[T], algorithm: &str) -> Vec<Op> {
+    struct Planner<'a, T> {
+        v: &'a [T],
+        idx: Vec<usize>,
+        ops: Vec<Op>,
+    }
+
+    impl<T: Ord> Planner<'_, T> {
+        fn cmp(&mut self, i: usize, j: usize) -> Ordering {
+            self.ops.push(Op::Compare(i, j));
+            self.v[self.idx[i]].cmp(&self.v[self.idx[j]])
+        }
+
+        fn swap(&mut self, i: usize, j: usize) {
+            self.ops.push(Op::Swap(i, j));
+            self.idx.swap(i, j);
+        }
+
+        fn move_to(&mut self, from: usize, to: usize) {
+            self.ops.push(Op::Move(from, to));
+            if from > to {
+                self.idx[to..=from].rotate_right(1);
+            } else {
+                self.idx[from..=to].rotate_left(1);
+            }
+        }
+
+        fn bubble(&mut self) {
+            let mut n = self.idx.len();
+            while n > 0 {
+                let mut nmax = 0;
+                for i in 1..n {
+                    if self.cmp(i - 1, i) == Ordering::Greater {
+                        self.swap(i - 1, i);
+                        nmax = i;
+                    }
+                }
+                n = nmax;
+            }
+        }
+
+        fn insertion(&mut self) {
+            for i in 1..self.idx.len() {
+                let mut j = i;
+                while j > 0 && self.cmp(j - 1, i) == Ordering::Greater {
+                    j -= 1;
+                }
+                if j < i {
+                    self.move_to(i, j);
+                }
+            }
+        }
+
+        fn selection(&mut self) {
+            for i in 0..self.idx.len().saturating_sub(1) {
+                let mut min = i;
+                for j in i + 1..self.idx.len() {
+                    if self.cmp(j, min) == Ordering::Less {
+                        min = j;
+                    }
+                }
+                if min != i {
+                    self.swap(i, min);
+                }
+            }
+        }
+
+        fn sift_down(&mut self, start: usize, len: usize) {
+            let mut i = start;
+            loop {
+                let mut child = i * 2 + 1;
+                if child >= len {
+                    break;
+                } else if child + 1 < len && self.cmp(child + 1, child) == Ordering::Greater {
+                    child += 1;
+                }
+
+                if self.cmp(child, i) == Ordering::Greater {
+                    self.swap(i, child);
+                    i = child;
+                } else {
+                    break;
+                }
+            }
+        }
+
+        fn heap(&mut self) {
+            let n = self.idx.len();
+            for i in (0..=n / 2).rev() {
+                self.sift_down(i, n);
+            }
+            for i in (1..n).rev() {
+                self.swap(0, i);
+                self.sift_down(0, i);
+            }
+        }
+    }
+
+    let mut planner = Planner {
+        v,
+        idx: (0..v.len()).collect(),
+        ops: vec![],
+    };
+
+    match algorithm {
+        "bubble" => planner.bubble(),
+        "insertion" => planner.insertion(),
+        "selection" => planner.selection(),
+        "heap" => planner.heap(),
+        _ => panic!("unknown algorithm {algorithm:?}"),
+    }
+
+    planner.ops
+}
+
+/// Sort `v` and return the ranges of indices of each page of `page_size`
+/// elements, in order, the last page being shorter if `page_size` does not
+/// divide `v.len()`.
+///
+/// Panics if `page_size` is zero.
+#[cfg(feature = "alloc")]
+pub fn sort_into_pages<T: Ord + Clone>(v: &mut [T], page_size: usize) -> Vec<Range<usize>> {
+    assert!(page_size > 0, "`page_size` must not be zero");
+    merge_sort_top_down_insert(v);
+
+    let n = v.len();
+    (0..n)
+        .step_by(page_size)
+        .map(|start| start..(start + page_size).min(n))
+        .collect()
+}
+
+/// Sort `v` with [`radix_sort`] and return whether it was a permutation of
+/// `0..v.len()`, that is, whether the sorted result is exactly `0, 1, 2, ...`,
+/// with no duplicate or out of range values.
+#[cfg(feature = "alloc")]
+pub fn sort_check_permutation(v: &mut [usize]) -> bool {
+    radix_sort(v);
+    v.iter().enumerate().all(|(i, &x)| i == x)
+}
+
+/// Sort `v` like [`sort_minimize_moves`] and return the permutation that
+/// [`restore`] needs to bring `v` back to its original order.
+#[cfg(feature = "alloc")]
+pub fn sort_with_undo<T: Ord>(v: &mut [T]) -> Vec<usize> {
+    let mut perm = argsort(v);
+
+    // the element that went from `perm[i]` to `i` must go back to `perm[i]`
+    let mut undo = vec![0; perm.len()];
+    for (i, &p) in perm.iter().enumerate() {
+        undo[p] = i;
+    }
+
+    apply_permutation_in_place(v, &mut perm);
+    undo
+}
+
+/// Undo a [`sort_with_undo`] of `v`, given the permutation it returned.
+///
+/// Panics if `undo` is not a permutation of `0..v.len()`.
+#[cfg(feature = "alloc")]
+pub fn restore<T>(v: &mut [T], undo: &[usize]) {
+    apply_permutation_in_place(v, &mut undo.to_vec());
+}
+
+/// **Sort-merge join**: sort both `left` and `right` by key with
+/// [`quick_sort_by`], then walk both in order calling `out` with the key
+/// and both values for every pair of elements with equal keys, so a key that
+/// appears `a` times on the left and `b` times on the right gives `a * b` calls.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn sort_merge_join<K: Ord, L, R>(
+    left: &mut [(K, L)],
+    right: &mut [(K, R)],
+    mut out: impl FnMut(&K, &L, &R),
+) {
+    quick_sort_by(left, |a, b| a.0.cmp(&b.0));
+    quick_sort_by(right, |a, b| a.0.cmp(&b.0));
+
+    let mut i = 0;
+    let mut j = 0;
+    while i < left.len() && j < right.len() {
+        match left[i].0.cmp(&right[j].0) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                // find the groups with this key on both sides, and join them
+                let key = &left[i].0;
+                let left_end = i + left[i..].iter().take_while(|x| x.0 == *key).count();
+                let right_end = j + right[j..].iter().take_while(|x| x.0 == *key).count();
+                for (_, l) in left[i..left_end].iter() {
+                    for (_, r) in right[j..right_end].iter() {
+                        out(key, l, r);
+                    }
+                }
+                i = left_end;
+                j = right_end;
+            }
+        }
+    }
+}
+
+/// Sort the vector inside `cell` with [`heap_sort`], borrowing it mutably only
+/// for the duration of the sort.
+///
+/// Panics if the vector is currently borrowed.
+#[cfg(feature = "alloc")]
+pub fn sort_refcell<T: Ord>(cell: &RefCell<Vec<T>>) {
+    heap_sort(&mut cell.borrow_mut());
+}
+
+/// Number of sampled elements per bucket when choosing the splitters of
+/// [`sample_sort`]: more samples give better balanced buckets.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+const SAMPLE_SORT_OVERSAMPLING: usize = 16;
+
+/// **Sample sort**, in parallel: move a random sample of
+/// `p * SAMPLE_SORT_OVERSAMPLING` elements to the front and sort it, with `p`
+/// the available parallelism, take `p - 1` evenly spaced splitters from it,
+/// move the elements to the `p` buckets between splitters, and sort each bucket
+/// with [`intro_sort`] on its own thread.  Elements are only moved, never
+/// cloned, and only the buckets go to other threads, hence just `Send`, but the
+/// classification into buckets is sequential.  Small slices are just sorted
+/// sequentially.  Not stable.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn sample_sort<T: Ord + Send>(v: &mut [T]) {
+    let p = std::thread::available_parallelism().map_or(1, |p| p.get());
+    let n = v.len();
+    if p < 2 || n < (p * SAMPLE_SORT_OVERSAMPLING).max(1 << 14) {
+        intro_sort(v);
+        return;
+    }
+
+    // the splitters are evenly spaced elements of the sorted sample
+    let sample = p * SAMPLE_SORT_OVERSAMPLING;
+    for i in 0..sample {
+        swap(v, i, fastrand::usize(i..n));
+    }
+    intro_sort(&mut v[..sample]);
+    let splitters: Vec<&T> = (1..p).map(|k| &v[k * SAMPLE_SORT_OVERSAMPLING]).collect();
+
+    // bucket of each element: the number of splitters not larger than it
+    let buckets: Vec<usize> = v
+        .iter()
+        .map(|x| splitters.partition_point(|&s| s <= x))
+        .collect();
+
+    // move the elements to their buckets, as in `counting_sort_by_key`
+    let mut offsets = vec![0; p + 1];
+    for &b in buckets.iter() {
+        offsets[b + 1] += 1;
+    }
+    for b in 0..p {
+        offsets[b + 1] += offsets[b];
+    }
+    let ends = offsets[1..].to_vec();
+    let mut perm = vec![0; n];
+    for (i, &b) in buckets.iter().enumerate() {
+        perm[offsets[b]] = i;
+        offsets[b] += 1;
+    }
+    apply_permutation_in_place(v, &mut perm);
+
+    std::thread::scope(|s| {
+        let mut rest = v;
+        let mut start = 0;
+        for end in ends {
+            let (bucket, tail) = rest.split_at_mut(end - start);
+            s.spawn(move || intro_sort(bucket));
+            rest = tail;
+            start = end;
+        }
+    });
+}
+
+/// **Merge sort**, stable, in parallel: split `v` into `p` contiguous chunks,
+/// with `p` the available parallelism, sort each on its own thread with
+/// [`merge_sort_bottom_up_insert`], then merge adjacent chunks pairwise, each
+/// pair on its own thread, doubling as in [`merge_sort_bottom_up`].  Since the
+/// chunks are contiguous and each merge takes from the left run on ties, equal
+/// elements keep their order.  Small slices are just sorted sequentially.
+#[cfg(feature = "std")]
+pub fn merge_sort_parallel_stable<T: Ord + Clone + Send>(v: &mut [T]) {
+    let p = std::thread::available_parallelism().map_or(1, |p| p.get());
+    let n = v.len();
+    if p < 2 || n < 1 << 14 {
+        merge_sort_bottom_up_insert(v);
+        return;
+    }
+
+    let chunk = n.div_ceil(p);
+    std::thread::scope(|s| {
+        for c in v.chunks_mut(chunk) {
+            s.spawn(move || merge_sort_bottom_up_insert(c));
+        }
+    });
+
+    let mut w: Vec<_> = v.to_vec();
+    let mut v_to_w = true;
+    let mut width = chunk;
+    while width < n {
+        let (from, to) = if v_to_w {
+            (&mut *v, &mut w[..])
+        } else {
+            (&mut w[..], &mut *v)
+        };
+        std::thread::scope(|s| {
+            for (from, to) in from.chunks_mut(2 * width).zip(to.chunks_mut(2 * width)) {
+                s.spawn(move || merge(from, width, to, &mut T::cmp));
+            }
+        });
+
+        v_to_w = !v_to_w;
+        width *= 2;
+    }
+
+    if !v_to_w {
+        v.clone_from_slice(&w);
+    }
+}
+
+/// Sub-arrays of [`par_merge_sort`] up to this length are sorted on a single
+/// thread, where spawning more would cost more than it saves.
+#[cfg(feature = "parallel")]
+const PAR_MERGE_SORT_CUTOFF: usize = 1 << 14;
+
+/// **Merge sort** top down, in parallel: the two halves are sorted on separate
+/// threads, recursively, and then merged.  The available parallelism is split
+/// between the halves, so no more threads run than there are cores, and
+/// sub-arrays shorter than [`PAR_MERGE_SORT_CUTOFF`], or without threads left,
+/// are sorted with [`merge_sort_bottom_up_insert`].  Stable.
+#[cfg(feature = "parallel")]
+pub fn par_merge_sort<T: Ord + Clone + Send>(v: &mut [T]) {
+    // sort `w` into `v`, holding the same elements, with up to `threads` threads
+    fn split_merge<T: Ord + Clone + Send>(w: &mut [T], v: &mut [T], threads: usize) {
+        if threads < 2 || w.len() <= PAR_MERGE_SORT_CUTOFF {
+            merge_sort_bottom_up_insert(v);
+        } else {
+            let half = w.len() / 2;
+            let (w1, w2) = w.split_at_mut(half);
+            let (v1, v2) = v.split_at_mut(half);
+            std::thread::scope(|s| {
+                s.spawn(|| split_merge(v1, w1, threads / 2));
+                split_merge(v2, w2, threads - threads / 2);
+            });
+            merge(w, half, v, &mut T::cmp);
+        }
+    }
+
+    let threads = std::thread::available_parallelism().map_or(1, |p| p.get());
+    let mut w: Vec<_> = v.to_vec();
+    split_merge(&mut w, v, threads);
+}
+
+/// Sub-arrays of [`par_quick_sort`] up to this length are insertion sorted
+/// instead of partitioned further.
+#[cfg(feature = "rayon")]
+const PAR_QUICK_SORT_CUTOFF: usize = 30;
+
+/// **Three-way Quicksort**, in parallel on the [rayon] thread pool: partition
+/// as [`quick_sort_3`] does, around the median of the first, middle and last
+/// elements, then sort the smaller and larger parts with [`rayon::join`], so
+/// they run on other threads of the pool when some are idle, or of the pool
+/// the caller already installed.  Sub-arrays up to [`PAR_QUICK_SORT_CUTOFF`]
+/// are sorted with [`insertion_sort`].  Not stable.
+///
+/// [rayon]: https://crates.io/crates/rayon
+#[cfg(feature = "rayon")]
+pub fn par_quick_sort<T: Ord + Send>(v: &mut [T]) {
+    if v.len() <= PAR_QUICK_SORT_CUTOFF {
+        insertion_sort(v);
+        return;
+    }
+
+    let pivot = median_of_3(v, 0, v.len() / 2, v.len() - 1);
+    swap(v, pivot, 0);
+    let (mid1, mid2) = partition_3_by(v, &mut T::cmp);
+    let (left, rest) = v.split_at_mut(mid1);
+    let right = &mut rest[mid2 - mid1..];
+    rayon::join(|| par_quick_sort(left), || par_quick_sort(right));
+}
+
+/// Return whether `v` has at most `max_inversions` inversions, pairs of
+/// elements out of order.  Inversions are counted by insertion sorting the
+/// indices of `v`, where each shift fixes one inversion, stopping as soon as
+/// the count exceeds the tolerance, so it runs in $O(n + max\_inversions)$.
+#[cfg(feature = "alloc")]
+pub fn is_nearly_sorted<T: Ord>(v: &[T], max_inversions: u64) -> bool {
+    let mut idx: Vec<usize> = (0..v.len()).collect();
+    let mut inversions = 0;
+    for i in 1..idx.len() {
+        let mut j = i;
+        while j > 0 && v[idx[j - 1]] > v[i] {
+            inversions += 1;
+            if inversions > max_inversions {
+                return false;
+            }
+            idx[j] = idx[j - 1];
+            j -= 1;
+        }
+        idx[j] = i;
+    }
+    true
+}
+
+/// Iterate over the elements of `v` in increasing order, sorting lazily: `v`
+/// is turned into a heap with the smallest element on top in $O(n)$, and each
+/// call to `next` removes the top in $O(\log n)$, so taking only the first `k`
+/// elements costs $O(n + k \log n)$.
+#[cfg(feature = "alloc")]
+pub fn sort_lazy<T: Ord>(mut v: Vec<T>) -> impl Iterator<Item = T> {
+    let mut reversed = |a: &T, b: &T| b.cmp(a);
+    for i in (0..=v.len() / 2).rev() {
+        sift_down(&mut v, i, &mut reversed);
+    }
+
+    core::iter::from_fn(move || {
+        if v.is_empty() {
+            return None;
+        }
+        let last = v.len() - 1;
+        swap(&mut v, 0, last);
+        let min = v.pop();
+        sift_down(&mut v, 0, &mut reversed);
+        min
+    })
+}
+
+/// Number of random triples of elements checked by [`sort_by_validated`].
+#[cfg(all(feature = "fastrand", feature = "std"))]
+const VALIDATION_SAMPLES: usize = 100;
+
+/// A violation of the strict weak ordering rules by a comparator, found by
+/// [`sort_by_validated`], with the indices of the elements involved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparatorError {
+    /// Comparing `a` with `b` is not the reverse of comparing `b` with `a`
+    /// (or an element is not equal to itself, when `a == b`).
+    NotAntisymmetric { a: usize, b: usize },
+    /// The order of `a` and `c` does not follow from their order with `b`.
+    NotTransitive { a: usize, b: usize, c: usize },
+}
+
+impl Display for ComparatorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ComparatorError::NotAntisymmetric { a, b } => {
+                write!(f, "comparator is not antisymmetric on elements {a} and {b}")
+            }
+            ComparatorError::NotTransitive { a, b, c } => {
+                write!(
+                    f,
+                    "comparator is not transitive on elements {a}, {b} and {c}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ComparatorError {}
+
+/// Sort `v` with `compare`, after checking it on `VALIDATION_SAMPLES` random
+/// triples of elements for the antisymmetry and transitivity a sort relies on.
+/// Sampling cannot prove a comparator correct, but catches common mistakes
+/// before they produce a garbled order; on error `v` is left untouched.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn sort_by_validated<T, F: Fn(&T, &T) -> Ordering>(
+    v: &mut [T],
+    compare: F,
+) -> Result<(), ComparatorError> {
+    let n = v.len();
+    if n == 0 {
+        return Ok(());
+    }
+
+    for _ in 0..VALIDATION_SAMPLES {
+        let (a, b, c) = (
+            fastrand::usize(..n),
+            fastrand::usize(..n),
+            fastrand::usize(..n),
+        );
+        for (x, y) in [(a, b), (b, c), (a, c), (a, a)] {
+            if compare(&v[x], &v[y]) != compare(&v[y], &v[x]).reverse() {
+                return Err(ComparatorError::NotAntisymmetric { a: x, b: y });
+            }
+        }
+
+        let ab = compare(&v[a], &v[b]);
+        let bc = compare(&v[b], &v[c]);
+        let ac = compare(&v[a], &v[c]);
+        let expected = match (ab, bc) {
+            (Ordering::Equal, _) => Some(bc),
+            (_, Ordering::Equal) => Some(ab),
+            _ if ab == bc => Some(ab),
+            _ => None,
+        };
+        if expected.is_some_and(|expected| expected != ac) {
+            return Err(ComparatorError::NotTransitive { a, b, c });
+        }
+    }
+
+    v.sort_by(compare);
+    Ok(())
+}
+
+/// Return the lengths of the longest sorted prefix and of the longest sorted
+/// suffix of `v`, both `v.len()` if `v` is sorted.
+pub fn sorted_extents<T: Ord>(v: &[T]) -> (usize, usize) {
+    let n = v.len();
+    let prefix = 1 + v.windows(2).take_while(|w| w[0] <= w[1]).count();
+    let suffix = 1 + v.windows(2).rev().take_while(|w| w[0] <= w[1]).count();
+    (prefix.min(n), suffix.min(n))
+}
+
+/// Sort `v` writing each element at most once, for memories where writes are
+/// expensive: as in **cycle sort**, each cycle of the sorting permutation is
+/// rotated through a single temporary, but the permutation is computed by
+/// sorting indices, in $O(n \log n)$ comparisons instead of $O(n^2)$.
+/// Elements already in place are not written.  Returns the number of writes to
+/// `v`, at most `v.len()`.
+#[cfg(feature = "alloc")]
+pub fn sort_min_writes<T: Ord + Clone>(v: &mut [T]) -> usize {
+    let mut perm = argsort(v);
+    let mut writes = 0;
+    for i in 0..v.len() {
+        if perm[i] == i {
+            continue;
+        }
+
+        // `v[i]` is kept aside while each element of its cycle moves into place
+        let first = v[i].clone();
+        let mut cur = i;
+        while perm[cur] != i {
+            let next = perm[cur];
+            v[cur] = v[next].clone();
+            perm[cur] = cur;
+            writes += 1;
+            cur = next;
+        }
+        v[cur] = first;
+        perm[cur] = cur;
+        writes += 1;
+    }
+    writes
+}
+
+/// Merge the sorted runs `v[..mid]` and `v[mid..]` with no auxiliary memory,
+/// by rotations: split the longer run in half, find where its middle element
+/// goes in the other run, rotate the elements in between to join the two
+/// lower and the two upper parts, and merge each of them the same way.
+/// Stable, $O(n \log n)$ moves.  `compare` as in [`sift_down`].
+fn merge_in_place<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mid: usize, compare: &mut F) {
+    let n = v.len();
+    if mid == 0 || mid == n {
+        return;
+    }
+    if n == 2 {
+        if compare(&v[1], &v[0]) == Ordering::Less {
+            swap(v, 0, 1);
+        }
+        return;
+    }
+
+    let (cut1, cut2) = if mid >= n - mid {
+        let cut1 = mid / 2;
+        let below = v[mid..].partition_point(|x| compare(x, &v[cut1]) == Ordering::Less);
+        (cut1, mid + below)
+    } else {
+        let cut2 = mid + (n - mid) / 2;
+        let below = v[..mid].partition_point(|x| compare(x, &v[cut2]) != Ordering::Greater);
+        (below, cut2)
+    };
+
+    v[cut1..cut2].rotate_left(mid - cut1);
+    let new_mid = cut1 + (cut2 - mid);
+    merge_in_place(&mut v[..new_mid], cut1, compare);
+    merge_in_place(&mut v[new_mid..], cut2 - new_mid, compare);
+}
+
+/// **Merge sort** top down with no auxiliary memory, other than the stack:
+/// small sub arrays are insertion sorted and the halves merged by rotations,
+/// as in [`merge_runs`] without a buffer.  Stable and needs no `Clone`, but
+/// the rotations make it $O(n \log^2 n)$, slower than the merge sorts that
+/// allocate a copy.
+pub fn merge_sort_in_place<T: Ord>(v: &mut [T]) {
+    merge_sort_in_place_by(v, T::cmp);
+}
+
+/// [`merge_sort_in_place`] ordering elements with `compare`, like
+/// [`slice::sort_by`].
+pub fn merge_sort_in_place_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    fn sort<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], compare: &mut F) {
+        if v.len() <= 16 {
+            insertion_sort_by(v, compare);
+        } else {
+            let half = v.len() / 2;
+            sort(&mut v[..half], compare);
+            sort(&mut v[half..], compare);
+            merge_in_place(v, half, compare);
+        }
+    }
+
+    sort(v, &mut compare);
+}
+
+/// [`merge_sort_in_place`] ordering elements by the key `f` returns for each,
+/// like [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn merge_sort_in_place_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    merge_sort_in_place_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// Merge the sorted runs `v[..mid]` and `v[mid..]` using `buf` as auxiliary
+/// memory: if it can hold the shorter run, that run is moved into `buf` and
+/// merged back in a single pass, otherwise `v` is merged in place by
+/// rotations, which is slower but needs no memory.  This lets callers cap
+/// the memory used.  Stable.
+///
+/// Panics if `mid > v.len()`.
+pub fn merge_runs<T: Ord + Clone>(v: &mut [T], mid: usize, buf: &mut [T]) {
+    let n = v.len();
+    assert!(mid <= n, "`mid` is out of range");
+    let left = mid;
+    let right = n - mid;
+
+    if left <= right && left <= buf.len() {
+        // merge front to back, the next write never passes the next read
+        buf[..left].clone_from_slice(&v[..mid]);
+        let (mut i, mut j) = (0, mid);
+        for k in 0..n {
+            if i < left && (j >= n || buf[i] <= v[j]) {
+                v[k] = buf[i].clone();
+                i += 1;
+            } else if i < left {
+                v[k] = v[j].clone();
+                j += 1;
+            } else {
+                break;
+            }
+        }
+    } else if right <= buf.len() {
+        // merge back to front, the next write never passes the next read
+        buf[..right].clone_from_slice(&v[mid..]);
+        let (mut i, mut j) = (mid, right);
+        for k in (0..n).rev() {
+            if j > 0 && (i == 0 || buf[j - 1] >= v[i - 1]) {
+                v[k] = buf[j - 1].clone();
+                j -= 1;
+            } else if j > 0 {
+                v[k] = v[i - 1].clone();
+                i -= 1;
+            } else {
+                break;
+            }
+        }
+    } else {
+        merge_in_place(v, mid, &mut T::cmp);
+    }
+}
+
+/// Restore the order of `v`, sorted until the element at `edited_index` was
+/// changed, by binary searching the new position of that element and shifting
+/// the elements in between by one: $O(\log n)$ comparisons and $O(n)$ moves
+/// at most, only the elements between both positions move.
+///
+/// Panics if `edited_index` is out of bounds.
+pub fn resort_after_edit<T: Ord>(v: &mut [T], edited_index: usize) {
+    assert!(edited_index < v.len(), "`edited_index` is out of bounds");
+    let e = edited_index;
+    if e > 0 && v[e] < v[e - 1] {
+        // moved down: goes after the elements on the left not larger than it
+        let pos = v[..e].partition_point(|x| *x <= v[e]);
+        v[pos..=e].rotate_right(1);
+    } else if e + 1 < v.len() && v[e] > v[e + 1] {
+        // moved up: goes after the elements on the right smaller than it
+        let pos = e + 1 + v[e + 1..].partition_point(|x| *x < v[e]);
+        v[e..pos].rotate_left(1);
+    }
+}
+
+/// Merge the sorted `src` into the sorted `dst[..dst_len]`, leaving `dst` with
+/// all of them in order.  Any elements of `dst` after `dst_len`, such as
+/// padding for the merged elements, are discarded.  `dst` is extended first
+/// and then filled from the back, where the merged elements never overwrite
+/// elements of `dst` not yet merged, so no other buffer is needed.  Stable.
+///
+/// Panics if `dst_len > dst.len()`.
+#[cfg(feature = "alloc")]
+pub fn merge_into_with_capacity<T: Ord + Clone>(dst: &mut Vec<T>, dst_len: usize, src: &[T]) {
+    assert!(dst_len <= dst.len(), "`dst_len` is out of bounds");
+    dst.truncate(dst_len);
+    dst.extend_from_slice(src);
+
+    let mut i = dst_len;
+    let mut j = src.len();
+    while j > 0 {
+        let k = i + j - 1;
+        if i > 0 && dst[i - 1] > src[j - 1] {
+            dst[k] = dst[i - 1].clone();
+            i -= 1;
+        } else {
+            dst[k] = src[j - 1].clone();
+            j -= 1;
+        }
+    }
+}
+
+/// **Heapsort** of `len` logical positions that are only accessed through the
+/// given callbacks: `less(i, j)` tells whether the element at `i` is smaller
+/// than the one at `j`, and `swap(i, j)` exchanges them.  Heapsort only needs
+/// compares and swaps and no memory, so this sorts storage that is not a slice,
+/// such as rows of a memory mapped file or a buffer behind FFI.
+pub fn sort_indexed<F, G>(len: usize, mut less: F, mut swap: G)
+where
+    F: FnMut(usize, usize) -> bool,
+    G: FnMut(usize, usize),
+{
+    // as `sift_down`, over the heap in `0..end`
+    fn sift_down<F, G>(less: &mut F, swap: &mut G, start: usize, end: usize)
+    where
+        F: FnMut(usize, usize) -> bool,
+        G: FnMut(usize, usize),
+    {
+        let mut i = start;
+        loop {
+            let mut child = i * 2 + 1;
+            if child >= end {
+                break;
+            } else if child + 1 < end && less(child, child + 1) {
+                child += 1;
+            }
+
+            if less(i, child) {
+                swap(i, child);
+                i = child;
+            } else {
+                break;
+            }
+        }
+    }
+
+    for i in (0..=len / 2).rev() {
+        sift_down(&mut less, &mut swap, i, len);
+    }
+
+    for i in (1..len).rev() {
+        swap(0, i);
+        sift_down(&mut less, &mut swap, 0, i);
+    }
+}
+
+/// **Insertion sort** with a budget of `max_moves` element moves, where
+/// inserting an element moves it and every element it shifts.  Returns `true`
+/// if `v` was sorted within the budget.  Otherwise stops before the insertion
+/// that would exceed it and returns `false`, leaving `v` a permutation of its
+/// input whose prefix up to that element is sorted.
+pub fn sort_move_capped<T: Ord>(v: &mut [T], max_moves: usize) -> bool {
+    let mut moves = 0;
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && v[j - 1] > v[i] {
+            j -= 1;
+        }
+
+        if j < i {
+            moves += i - j + 1;
+            if moves > max_moves {
+                return false;
+            }
+            v[j..=i].rotate_right(1);
+        }
+    }
+    true
+}
+
+/// Sort `v` grouping equal elements, with the most frequent groups first and
+/// groups of the same frequency in increasing order.  Elements are not
+/// deduplicated: `[1, 3, 2, 3, 2, 3]` sorts as `[3, 3, 3, 2, 2, 1]`.
+#[cfg(feature = "std")]
+pub fn sort_by_frequency<T: Ord + Clone + Hash + Eq>(v: &mut [T]) {
+    let mut frequency: HashMap<T, usize> = HashMap::new();
+    for x in v.iter() {
+        *frequency.entry(x.clone()).or_default() += 1;
+    }
+
+    v.sort_by(|a, b| frequency[b].cmp(&frequency[a]).then_with(|| a.cmp(b)));
+}
+
+/// Sort `v` when it has few distinct values that are expensive to compare or
+/// move, such as long strings repeated many times: each distinct value is
+/// interned, cloned once and counted, only the distinct values are sorted, and
+/// `v` is rewritten by cloning each of them back as many times as it was
+/// counted, as in a counting sort of their ranks.
+#[cfg(feature = "std")]
+pub fn sort_interned<T: Ord + Clone + Hash + Eq>(v: &mut [T]) {
+    let mut distinct: Vec<T> = vec![];
+    let mut counts: Vec<usize> = vec![];
+    let mut ids: HashMap<&T, usize> = HashMap::new();
+    for x in v.iter() {
+        let id = *ids.entry(x).or_insert_with(|| {
+            distinct.push(x.clone());
+            counts.push(0);
+            distinct.len() - 1
+        });
+        counts[id] += 1;
+    }
+
+    let mut start = 0;
+    for id in argsort(&distinct) {
+        let end = start + counts[id];
+        v[start..end].fill(distinct[id].clone());
+        start = end;
+    }
+}
+
+/// Comparator for the `sort_by` functions that compares elements by their
+/// `Ord` and counts each call in `counter`, to measure how many comparisons a
+/// sort does on some data.
+pub fn counting<T: Ord>(counter: &Cell<u64>) -> impl FnMut(&T, &T) -> Ordering + '_ {
+    move |a, b| {
+        counter.set(counter.get() + 1);
+        a.cmp(b)
+    }
+}
+
+#[cfg(feature = "count")]
+std::thread_local! {
+    /// Swaps done by [`swap`] on this thread, for [`count_swaps`].
+    static SWAPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Swap `v[a]` and `v[b]`, as `v.swap(a, b)`.  The sorts swap elements only
+/// through it, so that with the `count` feature it tallies them for
+/// [`count_swaps`]; without it, it is just `v.swap(a, b)`.
+#[inline]
+fn swap<T>(v: &mut [T], a: usize, b: usize) {
+    v.swap(a, b);
+    #[cfg(feature = "count")]
+    SWAPS.with(|swaps| swaps.set(swaps.get() + 1));
+}
+
+/// Run `f` and return the number of swaps the sorts of this crate did while
+/// it ran, of any elements, wrapped in [`Counted`] or not.  Swaps on other
+/// threads, like those of the parallel sorts, are not included, nor are
+/// elements moved other than by swapping two of them, like the merge sorts do.
+/// Only with the `count` feature, as tallying the swaps slows every sort down.
+#[cfg(feature = "count")]
+pub fn count_swaps<F: FnOnce()>(f: F) -> usize {
+    let before = SWAPS.with(Cell::get);
+    f();
+    SWAPS.with(Cell::get) - before
+}
+
+/// Tally of the comparisons and clones done on [`Counted`] elements.  The
+/// counters are atomic, so elements sorted on several threads can share them.
+#[derive(Debug, Default)]
+pub struct Counts {
+    comparisons: AtomicUsize,
+    clones: AtomicUsize,
+}
+
+impl Counts {
+    /// Counts starting at zero.
+    pub const fn new() -> Self {
+        Counts {
+            comparisons: AtomicUsize::new(0),
+            clones: AtomicUsize::new(0),
+        }
+    }
+
+    /// Number of comparisons, by `==`, `<`, `cmp` or any other operator.
+    pub fn comparisons(&self) -> usize {
+        self.comparisons.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Number of clones, as done by the merge sorts copying elements.
+    pub fn clones(&self) -> usize {
+        self.clones.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Set both counts back to zero.
+    pub fn reset(&self) {
+        self.comparisons.store(0, AtomicOrdering::Relaxed);
+        self.clones.store(0, AtomicOrdering::Relaxed);
+    }
+}
+
+/// Element wrapping a `value` that tallies in its [`Counts`] every comparison
+/// and clone, to measure how many of them any sort does on some data,
+/// independently of the machine.  Swaps and other moves are plain memory
+/// copies in Rust, invisible to the element; the swaps of the sorts of this
+/// crate are tallied by the sorts themselves, see `count_swaps`.
+#[derive(Debug)]
+pub struct Counted<'a, T> {
+    /// The wrapped value, which orders the element.
+    pub value: T,
+    counts: &'a Counts,
+}
+
+impl<'a, T> Counted<'a, T> {
+    /// Wrap `value`, tallying in `counts`.
+    pub fn new(value: T, counts: &'a Counts) -> Self {
+        Counted { value, counts }
+    }
+}
+
+impl<T: Clone> Clone for Counted<'_, T> {
+    fn clone(&self) -> Self {
+        self.counts.clones.fetch_add(1, AtomicOrdering::Relaxed);
+        Counted {
+            value: self.value.clone(),
+            counts: self.counts,
+        }
+    }
+}
+
+impl<T: Ord> PartialEq for Counted<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Counted<'_, T> {}
+
+impl<T: Ord> PartialOrd for Counted<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Counted<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.counts
+            .comparisons
+            .fetch_add(1, AtomicOrdering::Relaxed);
+        self.value.cmp(&other.value)
+    }
+}
+
+/// Count the distinct elements of `v`, sorting it in place with
+/// [`quick_sort_3`], which handles many equal elements well, and counting the
+/// runs of equal elements.  `v` is left sorted; see
+/// [`distinct_count_preserving`] to keep it untouched.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn distinct_count<T: Ord>(v: &mut [T]) -> usize {
+    quick_sort_3(v);
+    if v.is_empty() {
+        0
+    } else {
+        1 + v.windows(2).filter(|w| w[0] != w[1]).count()
+    }
+}
+
+/// [`distinct_count`] over a copy of `v`, leaving `v` untouched.
+#[cfg(all(feature = "fastrand", feature = "std"))]
+pub fn distinct_count_preserving<T: Ord + Clone>(v: &[T]) -> usize {
+    distinct_count(&mut v.to_vec())
+}
+
+/// Sort `v` made of already sorted segments, each starting at one of the
+/// indices in `boundaries` (and one starting at `0`, if not listed), by merging
+/// all of them at once: a heap holds the next element of each segment and the
+/// smallest is repeatedly taken, in $O(n \log k)$ for `k` segments.  Stable.
+///
+/// Panics if `boundaries` is not in increasing order or has an index past the
+/// end of `v`.  Debug builds also check that each segment is sorted.
+#[cfg(feature = "alloc")]
+pub fn merge_segments<T: Ord + Clone>(v: &mut [T], boundaries: &[usize]) {
+    let n = v.len();
+    assert!(
+        boundaries.windows(2).all(|w| w[0] <= w[1]),
+        "`boundaries` must be in increasing order"
+    );
+    assert!(
+        boundaries.last().is_none_or(|&b| b <= n),
+        "`boundaries` must be within `v`"
+    );
+
+    // `[start, end)` of each segment
+    let starts = core::iter::once(0).chain(boundaries.iter().copied());
+    let ends = boundaries.iter().copied().chain(core::iter::once(n));
+    let mut segments: Vec<(usize, usize)> = starts.zip(ends).filter(|(s, e)| s < e).collect();
+    for &(start, end) in segments.iter() {
+        debug_assert!(
+            is_sorted(&v[start..end]),
+            "segment at {start} is not sorted"
+        );
+    }
+
+    // the heap orders segments by their next element, then by their position
+    let mut w = Vec::with_capacity(n);
+    let mut heap: BinaryHeap<Reverse<(&T, usize)>> = segments
+        .iter()
+        .enumerate()
+        .map(|(k, &(start, _))| Reverse((&v[start], k)))
+        .collect();
+    while let Some(Reverse((x, k))) = heap.pop() {
+        w.push(x.clone());
+        let (start, end) = &mut segments[k];
+        *start += 1;
+        if start < end {
+            heap.push(Reverse((&v[*start], k)));
+        }
+    }
+
+    v.clone_from_slice(&w);
+}
+
+/// Sort timestamps that arrive almost in order, with a few late arrivals:
+/// the elements not smaller than all before them are kept in place, in order,
+/// while the late ones are set aside, insertion sorted, and merged back from
+/// the end, for $O(n + k^2)$ with `k` late elements.  If more than 1/16 of
+/// the elements are late, `v` is sorted with [`merge_sort_copy`] instead.
+/// Generic over `Copy` elements, for timestamps of any type, such as `u64`.
+#[cfg(feature = "alloc")]
+pub fn sort_timestamps<T: Ord + Copy>(v: &mut [T]) {
+    let n = v.len();
+    let mut late = vec![];
+    let mut kept = 0;
+    for i in 0..n {
+        if kept == 0 || v[i] >= v[kept - 1] {
+            v[kept] = v[i];
+            kept += 1;
+        } else {
+            late.push(v[i]);
+            if late.len() > n / 16 {
+                // too much disorder: `v[..kept]` and `late` hold what was in `v[..=i]`
+                v[kept..=i].copy_from_slice(&late);
+                merge_sort_copy(v);
+                return;
+            }
+        }
+    }
+
+    insertion_sort(&mut late);
+
+    // merge `late` into `v[..kept]` from the back, as in `merge_into_with_capacity`
+    let mut i = kept;
+    let mut j = late.len();
+    while j > 0 {
+        if i > 0 && v[i - 1] > late[j - 1] {
+            v[i + j - 1] = v[i - 1];
+            i -= 1;
+        } else {
+            v[i + j - 1] = late[j - 1];
+            j -= 1;
+        }
+    }
+}
+
+/// Sort `v` and return the rank of `query` in it: the number of elements
+/// strictly smaller than `query`, found by binary search.  If `query` is not
+/// in `v` this is still the index where it would be inserted, from `0` when it
+/// is smaller than all elements to `v.len()` when larger.
+#[cfg(feature = "alloc")]
+pub fn sort_and_rank<T: Ord + Clone>(v: &mut [T], query: &T) -> usize {
+    merge_sort_top_down_insert(v);
+    v.partition_point(|x| x < query)
+}
+
+/// Comparators of **Batcher's odd-even merge sort** network for `n` elements,
+/// in order, each a pair `(i, j)` with `i < j` to compare and exchange.  The
+/// network is built for the next power of two and the comparators that touch
+/// positions past `n` are dropped, as if those held elements larger than all.
+#[cfg(feature = "alloc")]
+pub fn build_network(n: usize) -> Vec<(usize, usize)> {
+    let size = n.next_power_of_two();
+    let mut network = vec![];
+    let mut p = 1;
+    while p < size {
+        let mut k = p;
+        while k >= 1 {
+            let mut j = k % p;
+            while j + k < size {
+                for i in 0..k.min(size - j - k) {
+                    let (a, b) = (i + j, i + j + k);
+                    if a / (2 * p) == b / (2 * p) && b < n {
+                        network.push((a, b));
+                    }
+                }
+                j += 2 * k;
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+    network
+}
+
+/// Run the sorting `network` over `v`: for each comparator `(i, j)`, in order,
+/// swap `v[i]` and `v[j]` if they are out of order.
+pub fn apply_network<T: Ord>(v: &mut [T], network: &[(usize, usize)]) {
+    for &(i, j) in network {
+        if v[i] > v[j] {
+            swap(v, i, j);
+        }
+    }
+}
+
+/// **Bitonic sort** of a slice whose length is a power of two: sort the first
+/// half in increasing and the second in decreasing order, recursively, which
+/// makes `v` *bitonic*, and merge it by comparing each element of the first
+/// half with the one half the length ahead, which leaves the smaller half of
+/// the elements on the first half, both halves bitonic again, and recursing.
+/// Slices of any other length are returned untouched.  Not stable.
+///
+/// The comparators depend only on the length, not on the elements, so it is a
+/// sorting network: each of its $O(\log^2 n)$ rounds is $n / 2$ independent
+/// compare-exchanges, done at once by SIMD lanes or GPU threads, for
+/// $O(n \log^2 n)$ comparisons in all.
+pub fn bitonic_sort<T: Ord>(v: &mut [T]) {
+    bitonic_sort_by(v, T::cmp);
+}
+
+/// [`bitonic_sort`] ordering elements with `compare`, like [`slice::sort_by`].
+pub fn bitonic_sort_by<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], mut compare: F) {
+    fn sort<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], ascending: bool, compare: &mut F) {
+        if v.len() > 1 {
+            let half = v.len() / 2;
+            sort(&mut v[..half], true, compare);
+            sort(&mut v[half..], false, compare);
+            merge(v, ascending, compare);
+        }
+    }
+
+    // sort the bitonic `v`
+    fn merge<T, F: FnMut(&T, &T) -> Ordering>(v: &mut [T], ascending: bool, compare: &mut F) {
+        if v.len() > 1 {
+            let half = v.len() / 2;
+            for i in 0..half {
+                compare_exchange_by(v, i, i + half, ascending, compare);
+            }
+            merge(&mut v[..half], ascending, compare);
+            merge(&mut v[half..], ascending, compare);
+        }
+    }
+
+    if v.len().is_power_of_two() {
+        sort(v, true, &mut compare);
+    }
+}
+
+/// [`bitonic_sort`] ordering elements by the key `f` returns for each, like
+/// [`slice::sort_by_key`].  `f` is called on every comparison.
+pub fn bitonic_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(v: &mut [T], mut f: F) {
+    bitonic_sort_by(v, |a, b| f(a).cmp(&f(b)));
+}
+
+/// **External sort** of the little endian `u64` values read from `input`,
+/// writing them in order to `output`, for data larger than memory: read
+/// chunks of `chunk_elems` values, sort each in memory with [`intro_sort`],
+/// spill it to a temporary file, and finally merge all chunks at once with a
+/// heap of their next values.  If the input fits in a single chunk it is
+/// written directly, without temporary files.
+///
+/// Fails if `input` does not hold a whole number of values, or on any I/O
+/// error.  Panics if `chunk_elems` is zero.
+#[cfg(feature = "std")]
+pub fn external_sort_stream<R: Read, W: Write>(
+    mut input: R,
+    output: W,
+    chunk_elems: usize,
+) -> io::Result<()> {
+    /// Temporary file holding a sorted chunk, removed when dropped.
+    struct Spill(PathBuf);
+
+    impl Drop for Spill {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    static SPILLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// Create a temporary file for reading and writing, with a name of its
+    /// own: if one is taken, even by a symbolic link planted by another user,
+    /// the next is tried, so an existing file is never opened.
+    fn create_spill() -> io::Result<(Spill, File)> {
+        loop {
+            let id = SPILLS.fetch_add(1, AtomicOrdering::Relaxed);
+            let path = env::temp_dir().join(format!("sort-{}-{id}.tmp", process::id()));
+            let created = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path);
+            match created {
+                Ok(file) => return Ok((Spill(path), file)),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn read_chunk<R: Read>(input: &mut R, chunk_elems: usize) -> io::Result<Vec<u64>> {
+        let mut bytes = vec![];
+        input.take(chunk_elems as u64 * 8).read_to_end(&mut bytes)?;
+        if bytes.len() % 8 != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "input is not a whole number of u64 values",
+            ));
+        }
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+            .collect())
+    }
+
+    /// Next value of a spilled chunk, `None` at its end.
+    fn read_value<R: Read>(reader: &mut R) -> io::Result<Option<u64>> {
+        let mut bytes = [0; 8];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => Ok(Some(u64::from_le_bytes(bytes))),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn write_all<W: Write>(output: &mut W, v: &[u64]) -> io::Result<()> {
+        for x in v {
+            output.write_all(&x.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    assert!(chunk_elems > 0, "`chunk_elems` must not be zero");
+    let mut output = BufWriter::new(output);
+
+    let mut chunk = read_chunk(&mut input, chunk_elems)?;
+    intro_sort(&mut chunk);
+    if chunk.len() < chunk_elems {
+        write_all(&mut output, &chunk)?;
+        return output.flush();
+    }
+
+    let mut spills = vec![];
+    while !chunk.is_empty() {
+        let (spill, file) = create_spill()?;
+        let mut writer = BufWriter::new(file);
+        write_all(&mut writer, &chunk)?;
+        // read the chunk back through the same handle, not by its name
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        spills.push((spill, file));
+
+        chunk = read_chunk(&mut input, chunk_elems)?;
+        intro_sort(&mut chunk);
+    }
+
+    // the heap orders chunks by their next value
+    let mut readers = vec![];
+    let mut heap = BinaryHeap::new();
+    for (k, (_, file)) in spills.iter_mut().enumerate() {
+        let mut reader = BufReader::new(file);
+        if let Some(x) = read_value(&mut reader)? {
+            heap.push(Reverse((x, k)));
+        }
+        readers.push(reader);
+    }
+    while let Some(Reverse((x, k))) = heap.pop() {
+        output.write_all(&x.to_le_bytes())?;
+        if let Some(next) = read_value(&mut readers[k])? {
+            heap.push(Reverse((next, k)));
+        }
+    }
+
+    output.flush()
+}
+
+/// Sort `v`, where values equal to `sentinel` mean "missing": they take no
+/// part in the ordering of the real values and are all grouped at the end of
+/// `v` with `sentinels_last`, or at its start otherwise.  The real values are
+/// compacted to the front and sorted with [`merge_sort_copy`].
+#[cfg(feature = "alloc")]
+pub fn sort_with_sentinel(v: &mut [u32], sentinel: u32, sentinels_last: bool) {
+    let mut k = 0;
+    for i in 0..v.len() {
+        if v[i] != sentinel {
+            v[k] = v[i];
+            k += 1;
+        }
+    }
+
+    merge_sort_copy(&mut v[..k]);
+    v[k..].fill(sentinel);
+    if !sentinels_last {
+        v.rotate_right(v.len() - k);
+    }
+}
+
+mod sealed {
+    /// Supertrait of [`super::SortExt`] that other crates cannot implement.
+    pub trait Sealed {}
+
+    impl<T> Sealed for [T] {}
+}
+
+/// Method syntax for the sorts of this crate, so `v.quick_sort()` can be
+/// written for `quick_sort(&mut v)`.  The methods forward to the functions of
+/// the same name, and need the same bounds on the elements.
+///
+/// The trait is sealed, implemented for slices only, as which methods it
+/// requires depends on the enabled features.
+pub trait SortExt<T>: sealed::Sealed {
+    /// Sort with [`gnome_sort`].
+    fn gnome_sort(&mut self);
+
+    /// Sort with [`bubble_sort`].
+    fn bubble_sort(&mut self);
+
+    /// Sort with [`cocktail_sort`].
+    fn cocktail_sort(&mut self);
+
+    /// Sort with [`odd_even_sort`].
+    fn odd_even_sort(&mut self);
+
+    /// Sort with [`comb_sort`].
+    fn comb_sort(&mut self);
+
+    /// Sort with [`insertion_sort`].
+    fn insertion_sort(&mut self);
+
+    /// Sort with [`selection_sort`].
+    fn selection_sort(&mut self);
+
+    /// Sort with [`pancake_sort`].
+    fn pancake_sort(&mut self);
+
+    /// Sort with [`shell_sort`].
+    fn shell_sort(&mut self);
+
+    /// Sort with [`heap_sort`].
+    fn heap_sort(&mut self);
+
+    /// Sort with [`smooth_sort`].
+    fn smooth_sort(&mut self);
+
+    /// Sort with [`intro_sort`].
+    fn intro_sort(&mut self);
+
+    /// Sort with [`quick_sort`].
+    #[cfg(all(feature = "fastrand", feature = "std"))]
+    fn quick_sort(&mut self);
+
+    /// Sort with [`quick_sort_3`].
+    #[cfg(all(feature = "fastrand", feature = "std"))]
+    fn quick_sort_3(&mut self);
+
+    /// Sort with [`merge_sort_top_down`].
+    #[cfg(feature = "alloc")]
+    fn merge_sort_top_down(&mut self)
+    where
+        T: Clone;
+
+    /// Sort with [`merge_sort_bottom_up`].
+    #[cfg(feature = "alloc")]
+    fn merge_sort_bottom_up(&mut self)
+    where
+        T: Clone;
+
+    /// Sort with [`merge_sort_in_place`].
+    fn merge_sort_in_place(&mut self);
+
+    /// Sort with [`tim_sort`].
+    #[cfg(feature = "alloc")]
+    fn tim_sort(&mut self)
+    where
+        T: Clone;
+}
+
+impl<T: Ord> SortExt<T> for [T] {
+    fn gnome_sort(&mut self) {
+        gnome_sort(self);
+    }
+
+    fn bubble_sort(&mut self) {
+        bubble_sort(self);
+    }
+
+    fn cocktail_sort(&mut self) {
+        cocktail_sort(self);
+    }
+
+    fn odd_even_sort(&mut self) {
+        odd_even_sort(self);
+    }
+
+    fn comb_sort(&mut self) {
+        comb_sort(self);
+    }
+
+    fn insertion_sort(&mut self) {
+        insertion_sort(self);
+    }
+
+    fn selection_sort(&mut self) {
+        selection_sort(self);
+    }
+
+    fn pancake_sort(&mut self) {
+        pancake_sort(self);
+    }
+
+    fn shell_sort(&mut self) {
+        shell_sort(self);
+    }
+
+    fn heap_sort(&mut self) {
+        heap_sort(self);
+    }
+
+    fn smooth_sort(&mut self) {
+        smooth_sort(self);
+    }
+
+    fn intro_sort(&mut self) {
+        intro_sort(self);
+    }
+
+    #[cfg(all(feature = "fastrand", feature = "std"))]
+    fn quick_sort(&mut self) {
+        quick_sort(self);
+    }
+
+    #[cfg(all(feature = "fastrand", feature = "std"))]
+    fn quick_sort_3(&mut self) {
+        quick_sort_3(self);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn merge_sort_top_down(&mut self)
+    where
+        T: Clone,
+    {
+        merge_sort_top_down(self);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn merge_sort_bottom_up(&mut self)
+    where
+        T: Clone,
+    {
+        merge_sort_bottom_up(self);
+    }
+
+    fn merge_sort_in_place(&mut self) {
+        merge_sort_in_place(self);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn tim_sort(&mut self)
+    where
+        T: Clone,
+    {
+        tim_sort(self);
+    }
+}
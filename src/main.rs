@@ -1,57 +1,114 @@
-use fastrand;
 use std::{
-    collections::{BTreeSet, HashMap},
-    fmt::Debug,
-    iter::repeat_with,
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    hint::black_box,
+    ops::{Deref, DerefMut},
+    process,
     time::Instant,
 };
 
-use sort::*;
+use serde::{Deserialize, Serialize};
+use sort::{generators::*, *};
 
-/// Assert that `v` is in increasing order.
-fn assert_ordered<T: Ord + Debug>(v: &[T]) {
-    for i in 1..v.len() {
-        assert!(
-            v[i - 1] <= v[i],
-            "ordering failed at index {i}: {:?} > {:?}",
-            v[i - 1],
-            v[i]
-        );
-    }
-}
+/// Seed of the random input sequences, the same on every run, so that runs
+/// can be compared.
+const SEED: u64 = 42;
 
-/// Return a sequence of `n` random `usize` values.
+/// [`generators::random_sequence`] with the fixed seed.
 fn random_sequence(n: usize) -> Vec<usize> {
-    repeat_with(|| fastrand::usize(..10000)).take(n).collect()
+    generators::random_sequence(n, SEED)
 }
 
-/// Return a sequence of `n` increasing `usize` values (i.e., `v[i] == i`).
-fn increasing_sequence(n: usize) -> Vec<usize> {
-    (0..n).collect()
+/// [`generators::skewed_sequence`] with the fixed seed.
+fn skewed_sequence(n: usize) -> Vec<usize> {
+    generators::skewed_sequence(n, SEED)
 }
 
-/// Return a sequence of `n` decreasing `usize` values.
-/// The reverse of [`increasing_sequence`].
-fn decreasing_sequence(n: usize) -> Vec<usize> {
-    (0..n).rev().collect()
+/// [`random_sequence`] as `u64` values.
+fn random_u64s(n: usize) -> Vec<u64> {
+    random_sequence(n).into_iter().map(|x| x as u64).collect()
 }
 
-/// Return a sequence of `n` equal `usize` values.
-fn equal_sequence(n: usize) -> Vec<usize> {
-    vec![42; n]
+/// [`generators::few_unique_sequence`] of 16 values as `u64`, with the fixed
+/// seed: merges take long runs of equal elements from the same half.
+fn few_unique_u64s(n: usize) -> Vec<u64> {
+    generators::few_unique_sequence(n, 16, SEED)
+        .into_iter()
+        .map(|x| x as u64)
+        .collect()
 }
 
-/// Return a sequence of `n - 1` equal values plus a smaller one.
-fn last_out_of_order(n: usize) -> Vec<usize> {
-    let mut v = vec![42; n];
-    *v.last_mut().unwrap() = 41;
-    v
+/// Return a sequence of `n` increasing values with `swaps` random pairs
+/// swapped, as [`generators::nearly_sorted_sequence`] with the fixed seed.
+/// The most common real workload: adaptive sorts, like insertion sort,
+/// Timsort or the adaptive merge sort, should run far faster on it than on
+/// random input, close to their best case when `swaps` is small.
+fn nearly_sorted(n: usize, swaps: usize) -> Vec<usize> {
+    generators::nearly_sorted_sequence(n, swaps, SEED)
+}
+
+/// [`nearly_sorted`] with 10 random pairs swapped, whatever the length.
+fn few_swaps_sequence(n: usize) -> Vec<usize> {
+    nearly_sorted(n, 10)
+}
+
+/// [`nearly_sorted`] with 1% of the elements swapped in random pairs, so the
+/// displacements grow with the length.
+fn nearly_sorted_sequence(n: usize) -> Vec<usize> {
+    nearly_sorted(n, n / 200)
+}
+
+/// [`generators::sawtooth_sequence`] in runs of 1000.
+fn sawtooth_sequence(n: usize) -> Vec<usize> {
+    generators::sawtooth_sequence(n, 1000)
+}
+
+/// A key comparing two fields, more expensive to compare than a `usize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    a: u64,
+    b: u64,
+}
+
+/// Convert a sequence of `usize` values into a sequence of [`Key`] in the same order.
+fn keys(v: Vec<usize>) -> Vec<Key> {
+    v.into_iter()
+        .map(|x| Key {
+            a: x as u64 / 100,
+            b: x as u64 % 100,
+        })
+        .collect()
+}
+
+/// [`random_sequence`] as [`Key`] values.
+fn random_keys(n: usize) -> Vec<Key> {
+    keys(random_sequence(n))
+}
+
+/// [`increasing_sequence`] as [`Key`] values.
+fn increasing_keys(n: usize) -> Vec<Key> {
+    keys(increasing_sequence(n))
+}
+
+/// [`decreasing_sequence`] as [`Key`] values.
+fn decreasing_keys(n: usize) -> Vec<Key> {
+    keys(decreasing_sequence(n))
+}
+
+/// [`equal_sequence`] as [`Key`] values.
+fn equal_keys(n: usize) -> Vec<Key> {
+    keys(equal_sequence(n))
+}
+
+/// [`generators::random_words`] with the fixed seed.
+fn random_words(n: usize) -> Vec<Vec<u8>> {
+    generators::random_words(n, SEED)
 }
 
-/// Return a sequence of a large value followed by `n - 1` equal ones larger than it.
-fn first_out_of_order(n: usize) -> Vec<usize> {
-    let mut v = vec![42; n];
-    *v.first_mut().unwrap() = 43;
+/// [`random_words`] in increasing order.
+fn sorted_words(n: usize) -> Vec<Vec<u8>> {
+    let mut v = random_words(n);
+    v.sort();
     v
 }
 
@@ -59,9 +116,10 @@ const REPETITIONS: usize = 100;
 const TIME_LIMIT: u128 = 500;
 
 /// Expands to calling the given sorting function (with name) with all given
-/// sorting order functions.  Collect timing statistics and print results.
+/// sorting order functions, with the repetitions, time limit and size of the
+/// given `Options`.  Collect timing statistics and print results.
 macro_rules! test_orders {
-    ( $name:expr , $sort_fn:expr , $( $vec_fn:expr ),+ $( , )? ) => {
+    ( $options:expr , $name:expr , $sort_fn:expr , [ $( $vec_fn:expr ),+ $( , )? ] ) => {
         {
             let max_name_length = [
                 $(
@@ -69,23 +127,24 @@ macro_rules! test_orders {
                 )+
             ].iter().max().copied().unwrap();
 
-            let mut results: HashMap<String, f64> = HashMap::new();
+            let mut results: BTreeMap<String, f64> = BTreeMap::new();
 
             $(
                 let vec_name = stringify!($vec_fn).to_string();
-                print!("testing {} with {:<width$} : ", $name, &vec_name, width = max_name_length);
-                let mut n = 128;
+                eprint!("testing {} with {:<width$} : ", $name, &vec_name, width = max_name_length);
+                let options: &Options = $options;
+                let mut n = options.size.unwrap_or(128);
                 loop {
                     let started = Instant::now();
-                    for _ in 0..REPETITIONS {
+                    for _ in 0..options.repetitions {
                         let mut v = $vec_fn(n);
                         $sort_fn(&mut v);
-                        assert_ordered(&v);
+                        assert!(is_sorted(&v));
                     }
                     let elapsed = started.elapsed();
-                    if elapsed.as_millis() >= TIME_LIMIT {
-                        let speed = (n as f64 / elapsed.as_secs_f64()) * REPETITIONS as f64;
-                        println!("{n:12} in {:5} ms = {:>15.2} elements/s", elapsed.as_millis(), speed);
+                    if elapsed.as_millis() >= options.time_limit_ms || options.size.is_some() {
+                        let speed = (n as f64 / elapsed.as_secs_f64()) * options.repetitions as f64;
+                        eprintln!("{n:12} in {:5} ms = {:>15.2} elements/s", elapsed.as_millis(), speed);
                         results.insert(vec_name, speed);
                         break;
                     } else {
@@ -99,9 +158,10 @@ macro_rules! test_orders {
     }
 }
 
-/// Expands to calling `test_orders` with all given sorting functions and their names.
+/// Expands to calling `test_orders` with all given sorting functions and their names,
+/// over the sorting order functions given in brackets, with the given `Options`.
 macro_rules! test_sorts {
-    ( $( $sort_fn:expr ),+ $( , )? ) => {
+    ( $options:expr , $vec_fns:tt , $( $sort_fn:expr ),+ $( , )? ) => {
         {
             let max_name_length = [
                 $(
@@ -109,18 +169,14 @@ macro_rules! test_sorts {
                 )+
             ].iter().max().copied().unwrap();
 
-            let mut results: HashMap<String, HashMap<String, f64>> = HashMap::new();
+            let mut results = Results::default();
 
             $(
                 let sort_name = stringify!($sort_fn).to_string();
                 let x = test_orders!(
+                    $options,
                     format!("{:<width$}", &sort_name, width = max_name_length), $sort_fn,
-                    random_sequence,
-                    increasing_sequence,
-                    decreasing_sequence,
-                    equal_sequence,
-                    last_out_of_order,
-                    first_out_of_order,
+                    $vec_fns
                 );
                 results.insert(sort_name, x);
             )+
@@ -130,11 +186,250 @@ macro_rules! test_sorts {
     }
 }
 
-fn tabulate(table: &HashMap<String, HashMap<String, f64>>) {
-    let sort_names: BTreeSet<_> = table.keys().collect();
-    let max_sort_name = sort_names.iter().map(|s| s.len()).max().unwrap_or(0);
+/// Default size of the inputs sorted to count comparisons, clones and swaps.
+const COUNT_SIZE: usize = 1000;
+
+/// Expands to sorting, with the given sorting function, each given sorting
+/// order function at the size of the given `Options`, or [`COUNT_SIZE`], with
+/// its elements wrapped in [`Counted`].  Return the comparisons, the clones and,
+/// with the `count` feature, the swaps done on each.
+macro_rules! count_orders {
+    ( $options:expr , $sort_fn:expr , [ $( $vec_fn:expr ),+ $( , )? ] ) => {
+        {
+            let options: &Options = $options;
+            let n = options.size.unwrap_or(COUNT_SIZE);
+            let counts = Counts::new();
+            let mut comparisons: BTreeMap<String, f64> = BTreeMap::new();
+            let mut clones: BTreeMap<String, f64> = BTreeMap::new();
+            let mut swaps: BTreeMap<String, f64> = BTreeMap::new();
+
+            $(
+                let vec_name = stringify!($vec_fn).to_string();
+                let mut v: Vec<_> = $vec_fn(n).into_iter().map(|x| Counted::new(x, &counts)).collect();
+                counts.reset();
+                if let Some(swapped) = swaps_of(|| $sort_fn(&mut v)) {
+                    swaps.insert(vec_name.clone(), swapped as f64);
+                }
+                comparisons.insert(vec_name.clone(), counts.comparisons() as f64);
+                clones.insert(vec_name, counts.clones() as f64);
+                assert!(is_sorted(&v));
+            )+
+
+            (comparisons, clones, swaps)
+        }
+    }
+}
+
+/// Run `sort` and return the swaps it did, as [`count_swaps`], with the `count`
+/// feature, and `None` without it.
+#[cfg(feature = "count")]
+fn swaps_of<F: FnOnce()>(sort: F) -> Option<usize> {
+    Some(count_swaps(sort))
+}
+
+/// Without the `count` feature the swaps are not tallied.
+#[cfg(not(feature = "count"))]
+fn swaps_of<F: FnOnce()>(sort: F) -> Option<usize> {
+    sort();
+    None
+}
+
+/// Expands to calling `count_orders` with all given sorting functions, over the
+/// sorting order functions given in brackets, with the given `Options`.
+macro_rules! count_sorts {
+    ( $options:expr , $vec_fns:tt , $( $sort_fn:expr ),+ $( , )? ) => {
+        {
+            let mut comparisons = Results::default();
+            let mut clones = Results::default();
+            let mut swaps = Results::default();
+
+            $(
+                let sort_name = stringify!($sort_fn).to_string();
+                eprintln!("counting {sort_name}");
+                let (x, y, z) = count_orders!($options, $sort_fn, $vec_fns);
+                comparisons.insert(sort_name.clone(), x);
+                clones.insert(sort_name.clone(), y);
+                if !z.is_empty() {
+                    swaps.insert(sort_name, z);
+                }
+            )+
 
-    let vec_names: BTreeSet<_> = table.iter().flat_map(|(_, v)| v.keys()).collect();
+            (comparisons, clones, swaps)
+        }
+    }
+}
+
+/// [`quick_sort_sampled_median`] with a sample of 3 elements.
+fn quick_sort_sampled_3<T: Ord>(v: &mut [T]) {
+    quick_sort_sampled_median(v, 3);
+}
+
+/// [`quick_sort_sampled_median`] with a sample of 5 elements.
+fn quick_sort_sampled_5<T: Ord>(v: &mut [T]) {
+    quick_sort_sampled_median(v, 5);
+}
+
+/// [`quick_sort_sampled_median`] with a sample of 9 elements.
+fn quick_sort_sampled_9<T: Ord>(v: &mut [T]) {
+    quick_sort_sampled_median(v, 9);
+}
+
+/// A key in the same order as `x`, but as expensive to compute as, say,
+/// parsing it out of a record.
+fn expensive_key(x: &usize) -> usize {
+    (0..100).fold(*x, |k, _| black_box(k))
+}
+
+/// [`quick_sort_by_key`] on the element itself, the cheapest key.
+fn quick_sort_by_cheap_key(v: &mut [usize]) {
+    quick_sort_by_key(v, |&x| x);
+}
+
+/// [`quick_sort_by_key`] on [`expensive_key`], computed on every comparison.
+fn quick_sort_by_expensive_key(v: &mut [usize]) {
+    quick_sort_by_key(v, expensive_key);
+}
+
+/// [`slice::sort_by_cached_key`] on the element itself, the cheapest key.
+fn sort_by_cached_cheap_key(v: &mut [usize]) {
+    v.sort_by_cached_key(|&x| x);
+}
+
+/// [`slice::sort_by_cached_key`] on [`expensive_key`], computed once per
+/// element.
+fn sort_by_cached_expensive_key(v: &mut [usize]) {
+    v.sort_by_cached_key(expensive_key);
+}
+
+/// Sort copies of `input` `repetitions` times, each in slices of `slice_len`
+/// elements one after the other with `sort_slice`, and return the elements
+/// sorted per second, printing it as [`test_orders`] does.
+fn time_slices(
+    name: &str,
+    input_name: &str,
+    input: &[usize],
+    slice_len: usize,
+    repetitions: usize,
+    mut sort_slice: impl FnMut(&mut [usize]),
+) -> f64 {
+    eprint!("testing {name} with {input_name} : ");
+    let started = Instant::now();
+    for _ in 0..repetitions {
+        let mut v = input.to_vec();
+        for slice in v.chunks_mut(slice_len) {
+            sort_slice(slice);
+            assert!(is_sorted(slice));
+        }
+    }
+    let elapsed = started.elapsed();
+    let n = input.len();
+    let speed = (n as f64 / elapsed.as_secs_f64()) * repetitions as f64;
+    eprintln!(
+        "{n:12} in {:5} ms = {:>15.2} elements/s",
+        elapsed.as_millis(),
+        speed
+    );
+    speed
+}
+
+/// Number of slices sorted one after the other by [`small_slices_results`].
+const SMALL_SLICES: usize = 10_000;
+/// Length of each of the [`SMALL_SLICES`].
+const SMALL_SLICE_LEN: usize = 32;
+
+/// Sort [`SMALL_SLICES`] random slices, as the rows of a matrix, with a merge
+/// sort allocating its buffer for each slice, and with one reusing a single
+/// buffer for all.
+fn small_slices_results(options: &Options) -> Results {
+    let input = random_sequence(SMALL_SLICES * SMALL_SLICE_LEN);
+    let input_name = format!("{SMALL_SLICES}_slices_of_{SMALL_SLICE_LEN}");
+    let time = |name: &str, sort_slice: &mut dyn FnMut(&mut [usize])| {
+        let speed = time_slices(
+            name,
+            &input_name,
+            &input,
+            SMALL_SLICE_LEN,
+            options.repetitions,
+            sort_slice,
+        );
+        (
+            name.to_string(),
+            BTreeMap::from([(input_name.clone(), speed)]),
+        )
+    };
+
+    let mut scratch = vec![];
+    Results(BTreeMap::from([
+        time("merge_sort_top_down", &mut |v| merge_sort_top_down(v)),
+        time("merge_sort_with_buffer", &mut |v| {
+            merge_sort_with_buffer(v, &mut scratch)
+        }),
+    ]))
+}
+
+/// Number of elements sorted by [`parallel_results`], enough for the threads
+/// to pay for themselves.
+#[cfg(feature = "parallel")]
+const PARALLEL_LEN: usize = 10_000_000;
+/// Times [`parallel_results`] sorts its input with each sort.
+#[cfg(feature = "parallel")]
+const PARALLEL_REPETITIONS: usize = 3;
+
+/// Sort [`PARALLEL_LEN`] random elements with [`merge_sort_top_down`] and with
+/// [`par_merge_sort`], its version on threads, printing the speedup.
+#[cfg(feature = "parallel")]
+fn parallel_results() -> Results {
+    let input = random_sequence(PARALLEL_LEN);
+    let input_name = format!("random_{PARALLEL_LEN}");
+    let time = |name: &str, sort: fn(&mut [usize])| {
+        let speed = time_slices(
+            name,
+            &input_name,
+            &input,
+            PARALLEL_LEN,
+            PARALLEL_REPETITIONS,
+            sort,
+        );
+        (
+            name.to_string(),
+            BTreeMap::from([(input_name.clone(), speed)]),
+        )
+    };
+
+    let results = Results(BTreeMap::from([
+        time("merge_sort_top_down", merge_sort_top_down),
+        time("par_merge_sort", par_merge_sort),
+    ]));
+    let speedup =
+        results["par_merge_sort"][&input_name] / results["merge_sort_top_down"][&input_name];
+    eprintln!("par_merge_sort speedup over merge_sort_top_down: {speedup:.2}x");
+    results
+}
+
+/// Benchmark results, elements/s keyed by sort name then by sequence name,
+/// saved and read as a JSON object of objects.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct Results(BTreeMap<String, BTreeMap<String, f64>>);
+
+impl Deref for Results {
+    type Target = BTreeMap<String, BTreeMap<String, f64>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Results {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+fn tabulate(table: &Results) {
+    let max_sort_name = table.keys().map(|s| s.len()).max().unwrap_or(0);
+
+    let vec_names: BTreeSet<_> = table.values().flat_map(|v| v.keys()).collect();
     let max_vec_name = vec_names.iter().map(|s| s.len()).max().unwrap_or(0);
 
     print!("{:>width$} |", "", width = max_sort_name);
@@ -143,34 +438,424 @@ fn tabulate(table: &HashMap<String, HashMap<String, f64>>) {
     }
     println!();
 
-    for sort_name in sort_names.iter() {
+    for (sort_name, row) in table.iter() {
         print!("{:<width$} |", sort_name, width = max_sort_name);
         for vec_name in vec_names.iter() {
-            let value = table.get(*sort_name).unwrap().get(*vec_name).unwrap();
-            print!(" {:>width$.2} |", value, width = max_vec_name);
+            match row.get(*vec_name) {
+                Some(value) => print!(" {:>width$.2} |", value, width = max_vec_name),
+                None => print!(" {:>width$} |", "", width = max_vec_name),
+            }
         }
         println!();
     }
 }
 
+/// Serialize `table` as CSV, a row per sort and a column per sequence, both in
+/// sorted order, leaving empty the cells of sequences a sort did not run on.
+fn to_csv(table: &Results) -> String {
+    let vec_names: BTreeSet<_> = table.values().flat_map(|v| v.keys()).collect();
+    let mut csv = String::from("sort");
+    for vec_name in vec_names.iter() {
+        csv += &format!(",{vec_name}");
+    }
+    csv += "\n";
+    for (sort_name, row) in table.iter() {
+        csv += sort_name;
+        for vec_name in vec_names.iter() {
+            match row.get(*vec_name) {
+                Some(value) => csv += &format!(",{value}"),
+                None => csv += ",",
+            }
+        }
+        csv += "\n";
+    }
+    csv
+}
+
+/// Print the percentage change of each result against `baseline`, and return
+/// the cells that got slower by more than `threshold` percent.
+fn compare(results: &Results, baseline: &Results, threshold: f64) -> Vec<String> {
+    let mut changes = Results::default();
+    let mut regressions = vec![];
+    for (sort_name, row) in results.iter() {
+        for (vec_name, speed) in row.iter() {
+            let Some(base) = baseline.get(sort_name).and_then(|b| b.get(vec_name)) else {
+                continue;
+            };
+            let change = (speed - base) / base * 100.0;
+            if change < -threshold {
+                regressions.push(format!("{sort_name} with {vec_name}: {change:.2}%"));
+            }
+            changes
+                .entry(sort_name.clone())
+                .or_default()
+                .insert(vec_name.clone(), change);
+        }
+    }
+
+    tabulate(&changes);
+    regressions
+}
+
+/// How the benchmark prints its results.
+#[derive(PartialEq)]
+enum Format {
+    /// A table per group of inputs, for reading.
+    Table,
+    /// CSV, a row per sort, for spreadsheets and plotting scripts.
+    Csv,
+    /// JSON, as saved by `--save`, for other programs.
+    Json,
+}
+
+/// Command line options of the benchmark.
+struct Options {
+    /// Write the results as JSON to this file.
+    save: Option<String>,
+    /// Compare the results against the JSON file saved by a previous run.
+    baseline: Option<String>,
+    /// Percentage of slowdown against the baseline considered a regression.
+    threshold: f64,
+    /// How to print the results.
+    format: Format,
+    /// Times each sort runs over each input before checking the time limit.
+    repetitions: usize,
+    /// Milliseconds the repetitions have to take, doubling the size until
+    /// they do, for a measurement long enough to be reliable.
+    time_limit_ms: u128,
+    /// Size to run every sort at, instead of doubling it up to the time limit.
+    size: Option<usize>,
+    /// Also count the comparisons, clones and swaps of each sort, printed as
+    /// tables only.
+    count: bool,
+}
+
+fn usage() -> ! {
+    eprintln!("usage: sort [--save FILE] [--baseline FILE] [--threshold PERCENT] [--csv | --json]");
+    eprintln!("            [--repetitions N] [--time-limit-ms MS] [--size N] [--count]");
+    eprintln!();
+    eprintln!("  --save FILE          write the results as JSON to FILE");
+    eprintln!("  --baseline FILE      compare against results saved by a previous run, exiting");
+    eprintln!("                       with status 1 if any got slower by more than the threshold");
+    eprintln!("  --threshold PERCENT  slowdown considered a regression (default 10)");
+    eprintln!("  --csv                print the results as CSV, a row per sort, instead of tables");
+    eprintln!("  --json               print the results as JSON instead of tables");
+    eprintln!("  --repetitions N      runs of each sort at each size (default {REPETITIONS})");
+    eprintln!("  --time-limit-ms MS   double the size until the runs take this long (default {TIME_LIMIT})");
+    eprintln!("  --size N             run only at this size, ignoring the time limit");
+    eprintln!(
+        "  --count              also print tables of the comparisons, clones and swaps of each"
+    );
+    eprintln!("                       sort, at the size given by --size (default {COUNT_SIZE});");
+    eprintln!("                       not with --csv or --json; swaps need the `count` feature");
+    process::exit(2);
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        save: None,
+        baseline: None,
+        threshold: 10.0,
+        format: Format::Table,
+        repetitions: REPETITIONS,
+        time_limit_ms: TIME_LIMIT,
+        size: None,
+        count: false,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| usage());
+        match arg.as_str() {
+            "--save" => options.save = Some(value()),
+            "--baseline" => options.baseline = Some(value()),
+            "--threshold" => options.threshold = value().parse().unwrap_or_else(|_| usage()),
+            "--csv" => options.format = Format::Csv,
+            "--json" => options.format = Format::Json,
+            "--repetitions" => options.repetitions = value().parse().unwrap_or_else(|_| usage()),
+            "--time-limit-ms" => {
+                options.time_limit_ms = value().parse().unwrap_or_else(|_| usage());
+            }
+            "--size" => options.size = Some(value().parse().unwrap_or_else(|_| usage())),
+            "--count" => options.count = true,
+            _ => usage(),
+        }
+    }
+
+    // the counts are not speeds to save or compare, so they are only tabulated
+    if options.count && options.format != Format::Table {
+        eprintln!("--count prints tables, it cannot be used with --csv or --json");
+        process::exit(2);
+    }
+
+    options
+}
+
 fn main() {
-    let results = test_sorts!(
+    let options = parse_args();
+
+    // read the baseline upfront, to not find out it is broken after running
+    let baseline = options.baseline.as_ref().map(|path| {
+        let text = fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("cannot read baseline {path}: {e}");
+            process::exit(2);
+        });
+        serde_json::from_str::<Results>(&text).unwrap_or_else(|e| {
+            eprintln!("invalid baseline {path}: {e}");
+            process::exit(2);
+        })
+    });
+
+    let mut results = test_sorts!(
+        &options,
+        [
+            random_sequence,
+            increasing_sequence,
+            decreasing_sequence,
+            equal_sequence,
+            last_out_of_order,
+            first_out_of_order,
+            skewed_sequence,
+            few_swaps_sequence,
+            nearly_sorted_sequence,
+        ],
         gnome_sort,
         bubble_sort,
+        cocktail_sort,
+        odd_even_sort,
+        comb_sort,
         selection_sort,
+        pancake_sort,
         insertion_sort,
         shell_sort,
         heap_sort,
+        heap_sort_topdown_build,
+        smooth_sort,
         quick_sort,
         quick_sort_3,
+        quick_sort_sampled_3,
+        quick_sort_sampled_5,
+        quick_sort_sampled_9,
+        quick_sort_median_of_3,
+        quick_sort_median_of_medians,
+        intro_sort,
+        quick_sort_hinted,
         merge_sort_top_down,
         merge_sort_top_down_insert,
+        merge_sort_top_down_adaptive,
+        merge_sort_copy,
         merge_sort_bottom_up,
         merge_sort_bottom_up_insert,
+        merge_sort_blocked,
+        merge_sort_in_place,
+        merge_sort_owned,
+        tim_sort,
         native_sort,
         native_unstable_sort,
+        // not a comparison sort, only here as a baseline for integer keys
+        radix_sort,
     );
 
-    println!();
-    tabulate(&results);
+    // comparing keys is more expensive, which favors sorts that compare less
+    let key_results = test_sorts!(
+        &options,
+        [random_keys, increasing_keys, decreasing_keys, equal_keys],
+        gnome_sort,
+        bubble_sort,
+        cocktail_sort,
+        odd_even_sort,
+        comb_sort,
+        selection_sort,
+        pancake_sort,
+        insertion_sort,
+        shell_sort,
+        heap_sort,
+        heap_sort_topdown_build,
+        smooth_sort,
+        quick_sort,
+        quick_sort_3,
+        quick_sort_sampled_3,
+        quick_sort_sampled_5,
+        quick_sort_sampled_9,
+        quick_sort_median_of_3,
+        quick_sort_median_of_medians,
+        intro_sort,
+        merge_sort_top_down,
+        merge_sort_top_down_insert,
+        merge_sort_top_down_adaptive,
+        merge_sort_copy,
+        merge_sort_bottom_up,
+        merge_sort_bottom_up_insert,
+        merge_sort_blocked,
+        merge_sort_in_place,
+        tim_sort,
+        native_sort,
+        native_unstable_sort,
+    );
+
+    // structured inputs, where the pivot choice matters
+    let pivot_results = test_sorts!(
+        &options,
+        [pipe_organ_sequence, sawtooth_sequence],
+        quick_sort,
+        quick_sort_3,
+        quick_sort_sampled_3,
+        quick_sort_median_of_3,
+        quick_sort_median_of_medians,
+        quick_sort_hinted,
+        intro_sort,
+    );
+
+    // keys computed on every comparison, or once per element
+    let by_key_results = test_sorts!(
+        &options,
+        [random_sequence],
+        quick_sort_by_cheap_key,
+        quick_sort_by_expensive_key,
+        sort_by_cached_cheap_key,
+        sort_by_cached_expensive_key,
+    );
+
+    // `Copy` elements, merged element by element or a run at a time
+    let copy_results = test_sorts!(
+        &options,
+        [random_u64s, few_unique_u64s],
+        merge_sort_top_down,
+        merge_sort_copy,
+    );
+
+    // byte strings, where comparisons look at many bytes
+    let word_results = test_sorts!(
+        &options,
+        [random_words, sorted_words],
+        quick_sort_3,
+        merge_sort_top_down,
+        sort_sequences,
+        native_sort,
+        native_unstable_sort,
+        radix_sort_bytes,
+    );
+
+    // many small slices sorted one after the other
+    let slice_results = small_slices_results(&options);
+
+    // one large input, sorted sequentially and on threads
+    #[cfg(feature = "parallel")]
+    let parallel_results = parallel_results();
+    #[cfg(not(feature = "parallel"))]
+    let parallel_results = Results::default();
+
+    if options.format == Format::Table {
+        println!();
+        tabulate(&results);
+        println!();
+        tabulate(&key_results);
+        println!();
+        tabulate(&pivot_results);
+        println!();
+        tabulate(&by_key_results);
+        println!();
+        tabulate(&copy_results);
+        println!();
+        tabulate(&word_results);
+        println!();
+        tabulate(&slice_results);
+        if !parallel_results.is_empty() {
+            println!();
+            tabulate(&parallel_results);
+        }
+    }
+
+    if options.count {
+        let (comparisons, clones, swaps) = count_sorts!(
+            &options,
+            [
+                random_sequence,
+                increasing_sequence,
+                decreasing_sequence,
+                equal_sequence,
+                few_swaps_sequence,
+                nearly_sorted_sequence,
+            ],
+            gnome_sort,
+            bubble_sort,
+            cocktail_sort,
+            odd_even_sort,
+            comb_sort,
+            selection_sort,
+            pancake_sort,
+            insertion_sort,
+            shell_sort,
+            heap_sort,
+            smooth_sort,
+            quick_sort,
+            quick_sort_3,
+            intro_sort,
+            merge_sort_top_down,
+            merge_sort_owned,
+            merge_sort_top_down_adaptive,
+            merge_sort_bottom_up,
+            merge_sort_blocked,
+            merge_sort_in_place,
+            tim_sort,
+            native_sort,
+            native_unstable_sort,
+        );
+
+        let n = options.size.unwrap_or(COUNT_SIZE);
+        println!();
+        println!("comparisons to sort {n} elements:");
+        tabulate(&comparisons);
+        println!();
+        println!("clones to sort {n} elements:");
+        tabulate(&clones);
+        println!();
+        if swaps.is_empty() {
+            println!("swaps are only counted with the `count` feature");
+        } else {
+            println!("swaps to sort {n} elements:");
+            tabulate(&swaps);
+        }
+    }
+
+    for (sort_name, row) in key_results
+        .0
+        .into_iter()
+        .chain(pivot_results.0)
+        .chain(by_key_results.0)
+        .chain(copy_results.0)
+        .chain(word_results.0)
+        .chain(slice_results.0)
+        .chain(parallel_results.0)
+    {
+        results.entry(sort_name).or_default().extend(row);
+    }
+
+    let json = serde_json::to_string_pretty(&results).expect("results are valid JSON") + "\n";
+    match options.format {
+        Format::Table => {}
+        Format::Csv => print!("{}", to_csv(&results)),
+        Format::Json => print!("{json}"),
+    }
+
+    if let Some(path) = &options.save {
+        if let Err(e) = fs::write(path, &json) {
+            eprintln!("cannot write results to {path}: {e}");
+            process::exit(2);
+        }
+    }
+
+    if let Some(baseline) = &baseline {
+        println!();
+        println!("change against baseline (%):");
+        let regressions = compare(&results, baseline, options.threshold);
+        if !regressions.is_empty() {
+            println!();
+            println!("regressions over {}%:", options.threshold);
+            for regression in regressions.iter() {
+                println!("  {regression}");
+            }
+            process::exit(1);
+        }
+    }
 }
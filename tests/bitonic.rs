@@ -0,0 +1,43 @@
+//! Bitonic sort on power-of-two lengths, and leaving other lengths untouched.
+
+use std::cmp::Reverse;
+
+use sort::*;
+
+/// `n` elements in a scrambled order, with repeats.
+fn scrambled(n: usize) -> Vec<usize> {
+    (0..n).map(|i| (i * 7919) % (n / 2 + 1)).collect()
+}
+
+#[test]
+fn sorts_power_of_two_lengths() {
+    for shift in 0..12 {
+        let input = scrambled(1 << shift);
+        for v in [input.clone(), input.iter().rev().copied().collect()] {
+            let mut expected = v.clone();
+            expected.sort();
+            let mut v = v;
+            bitonic_sort(&mut v);
+            assert_eq!(v, expected, "length {}", 1 << shift);
+        }
+    }
+}
+
+#[test]
+fn sorts_by_key_in_decreasing_order() {
+    let mut v = scrambled(256);
+    let mut expected = v.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    bitonic_sort_by_key(&mut v, |&x| Reverse(x));
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn leaves_other_lengths_untouched() {
+    for n in [0, 3, 5, 6, 7, 100, 1000] {
+        let input = scrambled(n);
+        let mut v = input.clone();
+        bitonic_sort(&mut v);
+        assert_eq!(v, input, "length {n}");
+    }
+}
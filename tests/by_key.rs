@@ -0,0 +1,55 @@
+//! Sorting by keys computed from the elements, recomputed on every comparison
+//! or cached once per element.
+#![cfg(feature = "alloc")]
+
+use sort::*;
+
+type CachedSortFn = fn(&mut [String], &mut dyn FnMut(&String) -> usize);
+
+/// Words of different lengths, with repeated lengths in a scrambled order.
+fn words() -> Vec<String> {
+    (0..500).map(|i| "x".repeat((i * 7919) % 23)).collect()
+}
+
+/// Sort [`words`] by length with `sort_fn`, counting the calls to the key.
+fn check_cached(name: &str, sort_fn: CachedSortFn) {
+    let mut v = words();
+    let mut calls = 0;
+    sort_fn(&mut v, &mut |s: &String| {
+        calls += 1;
+        s.len()
+    });
+    assert!(v.windows(2).all(|w| w[0].len() <= w[1].len()), "{name}");
+    assert_eq!(
+        calls,
+        v.len(),
+        "{name}: the key is computed once per element"
+    );
+}
+
+#[test]
+fn cached_key_is_computed_once_per_element() {
+    check_cached("heap_sort_by_cached_key", |v, f| {
+        heap_sort_by_cached_key(v, f)
+    });
+    check_cached("merge_sort_top_down_by_cached_key", |v, f| {
+        merge_sort_top_down_by_cached_key(v, f)
+    });
+    #[cfg(all(feature = "fastrand", feature = "std"))]
+    check_cached("quick_sort_by_cached_key", |v, f| {
+        quick_sort_by_cached_key(v, f)
+    });
+}
+
+#[test]
+fn uncached_key_is_computed_on_every_comparison() {
+    let mut v = words();
+    let mut calls = 0;
+    heap_sort_by_key(&mut v, |s| {
+        calls += 1;
+        s.len()
+    });
+    assert!(v.windows(2).all(|w| w[0].len() <= w[1].len()));
+    // two keys per comparison, and heapsort does more than `n log2(n)`
+    assert!(calls > 2 * v.len() * 8, "{calls} calls");
+}
@@ -0,0 +1,100 @@
+//! Checks of how sorted an input already is, and the sorts that rely on them.
+
+use sort::*;
+
+/// `values` wrapped to count comparisons in `counts`.
+fn counted<'a>(values: &[u32], counts: &'a Counts) -> Vec<Counted<'a, u32>> {
+    values.iter().map(|&x| Counted::new(x, counts)).collect()
+}
+
+#[test]
+fn ensure_sorted_only_checks_sorted_input() {
+    let counts = Counts::new();
+    let mut v = counted(&[1, 2, 2, 5, 8], &counts);
+    assert!(!ensure_sorted(&mut v));
+    assert_eq!(counts.comparisons(), 4, "one comparison per adjacent pair");
+    assert_eq!(
+        v.iter().map(|x| x.value).collect::<Vec<_>>(),
+        [1, 2, 2, 5, 8]
+    );
+
+    assert!(!ensure_sorted::<u32>(&mut []));
+    assert!(!ensure_sorted(&mut [7]));
+}
+
+#[test]
+fn ensure_sorted_fixes_corrupted_input() {
+    let mut v = [1, 2, 9, 4, 5, 0];
+    assert!(ensure_sorted(&mut v));
+    assert_eq!(v, [0, 1, 2, 4, 5, 9]);
+    assert!(!ensure_sorted(&mut v), "once fixed it stays sorted");
+}
+
+#[cfg(feature = "testutil")]
+mod nearly_sorted {
+    use super::*;
+    use sort::generators::*;
+
+    /// Inversions of `v`, counted pair by pair.
+    fn inversions(v: &[usize]) -> u64 {
+        let mut count = 0;
+        for i in 0..v.len() {
+            for j in i + 1..v.len() {
+                if v[i] > v[j] {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn sorted_has_no_inversions() {
+        assert!(is_nearly_sorted(&increasing_sequence(100), 0));
+        assert!(is_nearly_sorted(&equal_sequence(100), 0));
+        assert!(is_nearly_sorted::<u8>(&[], 0));
+        assert!(!is_nearly_sorted(&[2, 1, 3], 0));
+    }
+
+    #[test]
+    fn tolerance_is_exact() {
+        for input in [
+            nearly_sorted_sequence(100, 3, 1),
+            random_sequence(100, 2),
+            decreasing_sequence(30),
+        ] {
+            let count = inversions(&input);
+            assert!(is_nearly_sorted(&input, count));
+            if count > 0 {
+                assert!(!is_nearly_sorted(&input, count - 1));
+            }
+        }
+    }
+
+    #[test]
+    fn stops_once_over_tolerance() {
+        let counts = Counts::new();
+        let v: Vec<_> = (0..1000u32)
+            .rev()
+            .map(|x| Counted::new(x, &counts))
+            .collect();
+        assert!(!is_nearly_sorted(&v, 10));
+        // each comparison of reversed input finds an inversion
+        assert_eq!(counts.comparisons(), 11);
+    }
+}
+
+#[test]
+fn sorted_extents_of_sorted_and_reversed() {
+    assert_eq!(sorted_extents(&[1, 2, 2, 3]), (4, 4));
+    assert_eq!(sorted_extents(&[4, 3, 2, 1]), (1, 1));
+    assert_eq!(sorted_extents(&[7]), (1, 1));
+    assert_eq!(sorted_extents::<u8>(&[]), (0, 0));
+}
+
+#[test]
+fn sorted_extents_around_an_unsorted_middle() {
+    assert_eq!(sorted_extents(&[1, 2, 5, 3, 4, 6]), (3, 3));
+    assert_eq!(sorted_extents(&[1, 2, 3, 4, 0]), (4, 1));
+    assert_eq!(sorted_extents(&[9, 1, 2, 3, 4]), (1, 4));
+}
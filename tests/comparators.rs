@@ -0,0 +1,58 @@
+//! Sorting with user comparators, and checking them before trusting them.
+#![cfg(all(feature = "fastrand", feature = "std"))]
+
+use std::cmp::Ordering;
+
+use sort::*;
+
+/// A scrambled input of `0..3` repeated.
+fn input() -> Vec<u32> {
+    (0..30).map(|i| (i * 7) % 3).collect()
+}
+
+#[test]
+fn valid_comparator_sorts() {
+    let mut v: Vec<u32> = (0..100).map(|i| (i * 7919) % 20).collect();
+    assert_eq!(sort_by_validated(&mut v, u32::cmp), Ok(()));
+    assert!(is_sorted(&v));
+
+    let mut v = input();
+    assert_eq!(sort_by_validated(&mut v, |a, b| b.cmp(a)), Ok(()));
+    assert!(v.windows(2).all(|w| w[0] >= w[1]));
+
+    assert_eq!(sort_by_validated(&mut [] as &mut [u32], u32::cmp), Ok(()));
+}
+
+#[test]
+fn always_less_is_not_antisymmetric() {
+    let mut v = input();
+    let err = sort_by_validated(&mut v, |_, _| Ordering::Less).unwrap_err();
+    assert!(
+        matches!(err, ComparatorError::NotAntisymmetric { .. }),
+        "{err}"
+    );
+    assert_eq!(v, input(), "left untouched on error");
+}
+
+#[test]
+fn rock_paper_scissors_is_not_transitive() {
+    // each value is smaller than the next one, and `2` smaller than `0`
+    let beats = |a: &u32, b: &u32| {
+        if (a + 1) % 3 == *b {
+            Ordering::Less
+        } else if (b + 1) % 3 == *a {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    };
+    let mut v = input();
+    // about a fifth of the sampled triples show it, so one is all but certain
+    let err = sort_by_validated(&mut v, beats).unwrap_err();
+    assert!(
+        matches!(err, ComparatorError::NotTransitive { .. }),
+        "{err}"
+    );
+    assert!(err.to_string().contains("not transitive"));
+    assert_eq!(v, input());
+}
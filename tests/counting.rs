@@ -0,0 +1,79 @@
+//! Counting the comparisons, flips and swaps sorts do, against counts worked
+//! out by hand on tiny inputs.
+
+use std::cell::Cell;
+
+use sort::*;
+
+/// Comparisons `insertion_sort_by` does on `v`, as tallied by `counting`.
+fn insertion_sort_comparisons(mut v: Vec<u32>) -> u64 {
+    let counter = Cell::new(0);
+    insertion_sort_by(&mut v, counting(&counter));
+    assert!(is_sorted(&v));
+    counter.get()
+}
+
+#[test]
+fn counting_comparator_on_insertion_sort() {
+    // `1` is compared with `3` only; `2` with `3` and then `1`
+    assert_eq!(insertion_sort_comparisons(vec![3, 1, 2]), 3);
+    // each element is compared with every one before it, 1 + 2 + 3
+    assert_eq!(insertion_sort_comparisons(vec![4, 3, 2, 1]), 6);
+    // each element is compared only with the one before it
+    assert_eq!(insertion_sort_comparisons(vec![1, 2, 3, 4]), 3);
+    assert_eq!(insertion_sort_comparisons(vec![]), 0);
+}
+
+#[test]
+fn counting_comparator_is_shared() {
+    let counter = Cell::new(0);
+    let mut a = [2, 1];
+    let mut b = [1, 2, 3];
+    insertion_sort_by(&mut a, counting(&counter));
+    insertion_sort_by(&mut b, counting(&counter));
+    assert_eq!(counter.get(), 1 + 2);
+}
+
+#[test]
+fn pancake_flips() {
+    // `3` goes to the end in one flip, then `2` in another
+    let mut v = [3, 1, 2];
+    assert_eq!(pancake_sort_counted(&mut v), 2);
+    assert_eq!(v, [1, 2, 3]);
+
+    // `3` is flipped to the front and then to the end, then `2` in one flip
+    let mut v = [1, 3, 2];
+    assert_eq!(pancake_sort_counted(&mut v), 3);
+    assert_eq!(v, [1, 2, 3]);
+
+    let mut v = [1, 2, 3];
+    assert_eq!(pancake_sort_counted(&mut v), 0);
+}
+
+#[cfg(feature = "count")]
+#[test]
+fn swaps_of_each_sort() {
+    // bubble sort swaps each inverted pair once
+    let mut v = [4, 3, 2, 1];
+    assert_eq!(count_swaps(|| bubble_sort(&mut v)), 6);
+
+    // insertion sort shifts elements instead of swapping them
+    let mut v = [4, 3, 2, 1];
+    assert_eq!(count_swaps(|| insertion_sort(&mut v)), 0);
+
+    // selection sort swaps at most once per position but the last, and not
+    // the elements already in place
+    let mut v = [4, 3, 2, 1];
+    assert_eq!(count_swaps(|| selection_sort(&mut v)), 2);
+    let mut v = [1, 2, 3, 4];
+    assert_eq!(count_swaps(|| selection_sort(&mut v)), 0);
+
+    // only the swaps done inside count, including those of nested counts
+    let mut v = [2, 1];
+    let mut w = [2, 1];
+    let inner = count_swaps(|| {
+        bubble_sort(&mut v);
+        assert_eq!(count_swaps(|| bubble_sort(&mut w)), 1);
+    });
+    assert_eq!(inner, 2);
+}
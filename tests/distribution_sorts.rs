@@ -0,0 +1,171 @@
+//! The sorts that are not comparison sorts, distributing elements by their
+//! digits or keys, against the native sort.
+#![cfg(feature = "testutil")]
+
+use sort::{generators::*, *};
+
+/// `n` keys spread over the whole range of `usize`, by a multiplicative hash.
+fn full_range_sequence(n: usize) -> Vec<usize> {
+    (0..n)
+        .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15_u64 as usize))
+        .collect()
+}
+
+#[test]
+fn radix_sort_matches_native() {
+    for n in [0, 1, 2, 100, 5000] {
+        for input in [
+            random_sequence(n, n as u64),
+            full_range_sequence(n),
+            decreasing_sequence(n),
+            equal_sequence(n),
+        ] {
+            let mut v = input.clone();
+            radix_sort(&mut v);
+            let mut expected = input;
+            expected.sort();
+            assert_eq!(v, expected);
+        }
+    }
+}
+
+#[test]
+fn radix_sort_extreme_keys() {
+    let mut v = vec![
+        usize::MAX,
+        0,
+        usize::MAX / 2 + 1,
+        255,
+        256,
+        usize::MAX - 1,
+        0,
+    ];
+    radix_sort(&mut v);
+    assert_eq!(
+        v,
+        [
+            0,
+            0,
+            255,
+            256,
+            usize::MAX / 2 + 1,
+            usize::MAX - 1,
+            usize::MAX
+        ]
+    );
+}
+
+#[test]
+fn sort_sequences_shared_prefixes_and_lengths() {
+    let mut v: Vec<Vec<u8>> = vec![
+        b"abc".to_vec(),
+        b"ab".to_vec(),
+        vec![],
+        b"abd".to_vec(),
+        b"a".to_vec(),
+        b"abc".to_vec(),
+        b"b".to_vec(),
+        vec![],
+        b"abcd".to_vec(),
+    ];
+    sort_sequences(&mut v);
+    let expected: [&[u8]; 9] = [b"", b"", b"a", b"ab", b"abc", b"abc", b"abcd", b"abd", b"b"];
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn sort_sequences_matches_native() {
+    for n in [0, 1, 2, 17, 100, 1000] {
+        // few distinct elements and short lengths, for long shared prefixes
+        let mut lengths = few_unique_sequence(n, 6, n as u64).into_iter();
+        let mut elements = few_unique_sequence(6 * n, 3, 1).into_iter();
+        let input: Vec<Vec<usize>> = (0..n)
+            .map(|_| {
+                let len = lengths.next().unwrap();
+                elements.by_ref().take(len).collect()
+            })
+            .collect();
+
+        let mut v = input.clone();
+        sort_sequences(&mut v);
+        let mut expected = input;
+        expected.sort();
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn sort_sequences_long_identical_sequences() {
+    // one level per shared element would overflow the stack
+    let long = vec![7u8; 1_000_000];
+    let mut v = vec![long.clone(); 20];
+    v.push(vec![7; 10]);
+    v.push(vec![8]);
+    sort_sequences(&mut v);
+    assert_eq!(v[0], [7; 10]);
+    assert!(v[1..21].iter().all(|s| *s == long));
+    assert_eq!(v[21], [8]);
+}
+
+#[test]
+fn counting_sort_by_key_is_stable() {
+    let keys = few_unique_sequence(1000, 20, 3);
+    let mut v: Vec<(usize, usize)> = keys
+        .into_iter()
+        .enumerate()
+        .map(|(id, k)| (k, id))
+        .collect();
+    counting_sort_by_key(&mut v, 19, |&(k, _)| k);
+    // sorting the pairs orders equal keys by `id`, their original position
+    let mut expected = v.clone();
+    expected.sort();
+    assert_eq!(v, expected);
+}
+
+#[test]
+#[should_panic(expected = "larger than max")]
+fn counting_sort_by_key_rejects_large_key() {
+    counting_sort_by_key(&mut [1usize, 5, 2], 3, |&x| x);
+}
+
+#[test]
+fn radix_sort_bytes_shared_prefixes_and_lengths() {
+    let words: [&[u8]; 9] = [b"abc", b"ab", b"", b"abd", b"a", b"abc", b"b", b"", b"abcd"];
+    // three copies of each, past the insertion sort cutoff, with six empty
+    let mut v: Vec<Vec<u8>> = (0..3).flat_map(|_| words.map(<[u8]>::to_vec)).collect();
+    let mut expected = v.clone();
+    expected.sort();
+    radix_sort_bytes(&mut v);
+    assert_eq!(v, expected);
+    assert!(v[..6].iter().all(|s| s.is_empty()));
+}
+
+#[test]
+fn radix_sort_bytes_matches_native() {
+    for n in [0, 1, 2, 17, 100, 1000, 10_000] {
+        let input = random_words(n, n as u64);
+        let mut v = input.clone();
+        radix_sort_bytes(&mut v);
+        let mut expected = input;
+        expected.sort();
+        assert_eq!(v, expected, "n = {n}");
+    }
+
+    // every byte value, including those past ASCII
+    let mut v: Vec<Vec<u8>> = (0..=255u8).rev().map(|b| vec![b, b]).collect();
+    radix_sort_bytes(&mut v);
+    assert!(v.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn radix_sort_bytes_long_identical_strings() {
+    // one level per shared byte would overflow the stack
+    let long = vec![7u8; 1_000_000];
+    let mut v = vec![long.clone(); 20];
+    v.push(vec![7; 10]);
+    v.push(vec![8]);
+    radix_sort_bytes(&mut v);
+    assert_eq!(v[0], [7; 10]);
+    assert!(v[1..21].iter().all(|s| *s == long));
+    assert_eq!(v[21], [8]);
+}
@@ -0,0 +1,85 @@
+//! External sort of `u64` streams, through in-memory readers and writers.
+#![cfg(all(feature = "fastrand", feature = "std"))]
+
+use std::io::{self, ErrorKind};
+
+/// The little endian bytes of `values`.
+fn to_bytes(values: &[u64]) -> Vec<u8> {
+    values.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// The values of the little endian `bytes`.
+fn from_bytes(bytes: &[u8]) -> Vec<u64> {
+    bytes
+        .chunks_exact(8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+/// Sort the `values` stream into memory.
+fn external_sort(values: &[u64], chunk_elems: usize) -> io::Result<Vec<u64>> {
+    let mut output = vec![];
+    sort::external_sort_stream(&to_bytes(values)[..], &mut output, chunk_elems)?;
+    Ok(from_bytes(&output))
+}
+
+#[test]
+fn round_trips_shuffled_values() {
+    let n = 100_000;
+    let mut values: Vec<u64> = (0..n).collect();
+    fastrand::Rng::with_seed(1).shuffle(&mut values);
+
+    // many spilled chunks, a last partial one, exactly one full chunk, and
+    // the single chunk that needs no spilling
+    for chunk_elems in [1000, 3001, n as usize, 2 * n as usize] {
+        let sorted = external_sort(&values, chunk_elems).unwrap();
+        assert!(
+            sorted.iter().copied().eq(0..n),
+            "chunk_elems = {chunk_elems}"
+        );
+    }
+}
+
+#[test]
+fn duplicates_and_empty_input() {
+    let values: Vec<u64> = (0..5000).map(|i| (i * 7919) % 10).collect();
+    let mut expected = values.clone();
+    expected.sort();
+    assert_eq!(external_sort(&values, 100).unwrap(), expected);
+
+    assert!(external_sort(&[], 100).unwrap().is_empty());
+}
+
+#[test]
+fn rejects_partial_values() {
+    let mut bytes = to_bytes(&[3, 1, 2]);
+    bytes.pop();
+    let err = sort::external_sort_stream(&bytes[..], &mut vec![], 2).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[cfg(unix)]
+#[test]
+fn never_follows_planted_symlinks() {
+    use std::{env, fs, os::unix::fs::symlink, process};
+
+    // symbolic links under the names the spills would take, all to a file
+    // the sort must not write to
+    let dir = env::temp_dir();
+    let victim = dir.join(format!("sort-victim-{}", process::id()));
+    fs::write(&victim, b"untouched").unwrap();
+    let planted: Vec<_> = (0..500)
+        .map(|id| dir.join(format!("sort-{}-{id}.tmp", process::id())))
+        .filter(|link| symlink(&victim, link).is_ok())
+        .collect();
+
+    let values: Vec<u64> = (0..1000).rev().collect();
+    let sorted = external_sort(&values, 100);
+
+    for link in &planted {
+        fs::remove_file(link).unwrap();
+    }
+    assert_eq!(fs::read(&victim).unwrap(), b"untouched");
+    fs::remove_file(&victim).unwrap();
+    assert!(sorted.unwrap().iter().copied().eq(0..1000));
+}
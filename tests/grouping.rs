@@ -0,0 +1,111 @@
+//! Sorts grouping equal elements together, and counting the groups.
+#![cfg(feature = "std")]
+
+use sort::*;
+
+#[test]
+fn most_frequent_first() {
+    let mut v = vec![5, 1, 7, 5, 7, 5];
+    sort_by_frequency(&mut v);
+    assert_eq!(v, [5, 5, 5, 7, 7, 1]);
+}
+
+#[test]
+fn equal_frequencies_in_increasing_order() {
+    let mut v = vec!["b", "c", "a", "c", "b", "a", "d"];
+    sort_by_frequency(&mut v);
+    assert_eq!(v, ["a", "a", "b", "b", "c", "c", "d"]);
+
+    let mut v: Vec<u8> = vec![];
+    sort_by_frequency(&mut v);
+    assert!(v.is_empty());
+}
+
+#[test]
+fn sentinels_grouped_at_either_end() {
+    const MISSING: u32 = u32::MAX;
+    let input = [7, MISSING, 3, MISSING, 9, 1, MISSING, 3];
+
+    let mut v = input;
+    sort_with_sentinel(&mut v, MISSING, true);
+    assert_eq!(v, [1, 3, 3, 7, 9, MISSING, MISSING, MISSING]);
+
+    let mut v = input;
+    sort_with_sentinel(&mut v, MISSING, false);
+    assert_eq!(v, [MISSING, MISSING, MISSING, 1, 3, 3, 7, 9]);
+}
+
+#[test]
+fn sentinel_takes_no_part_in_the_order() {
+    // a sentinel of `0` still goes last, after larger real values
+    let mut v = [5, 0, 2, 0, 8];
+    sort_with_sentinel(&mut v, 0, true);
+    assert_eq!(v, [2, 5, 8, 0, 0]);
+
+    let mut all = [4; 5];
+    sort_with_sentinel(&mut all, 4, false);
+    assert_eq!(all, [4; 5]);
+
+    let mut none = [3, 2, 1];
+    sort_with_sentinel(&mut none, 9, true);
+    assert_eq!(none, [1, 2, 3]);
+}
+
+#[cfg(feature = "fastrand")]
+mod distinct {
+    use super::*;
+
+    #[test]
+    fn empty_equal_and_distinct() {
+        for (input, expected) in [
+            (vec![], 0),
+            (vec![4; 100], 1),
+            ((0..100).rev().collect(), 100),
+            (vec![3, 1, 3, 2, 1, 3], 3),
+        ] {
+            assert_eq!(distinct_count_preserving(&input), expected);
+
+            let mut v = input.clone();
+            assert_eq!(distinct_count(&mut v), expected);
+            assert!(is_sorted(&v), "left sorted");
+        }
+    }
+
+    #[test]
+    fn preserving_leaves_input_untouched() {
+        let v = vec![5, 3, 5, 1];
+        assert_eq!(distinct_count_preserving(&v), 3);
+        assert_eq!(v, [5, 3, 5, 1]);
+    }
+}
+
+#[test]
+fn interned_long_strings() {
+    let distinct: Vec<String> = ["kiwi", "apple", "mango", "banana", "cherry"]
+        .iter()
+        .map(|word| word.repeat(200))
+        .collect();
+    let mut v: Vec<String> = (0..5000)
+        .map(|i| distinct[(i * 7919) % distinct.len()].clone())
+        .collect();
+    let mut expected = v.clone();
+    expected.sort();
+
+    sort_interned(&mut v);
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn interned_edge_cases() {
+    let mut empty: Vec<u8> = vec![];
+    sort_interned(&mut empty);
+    assert!(empty.is_empty());
+
+    let mut same = vec!["x"; 10];
+    sort_interned(&mut same);
+    assert_eq!(same, ["x"; 10]);
+
+    let mut distinct = vec![3, 1, 2];
+    sort_interned(&mut distinct);
+    assert_eq!(distinct, [1, 2, 3]);
+}
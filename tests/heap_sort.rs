@@ -0,0 +1,164 @@
+//! Heapsort variants and the partial sorts and iterators built on heaps.
+#![cfg(feature = "testutil")]
+
+use sort::{generators::*, *};
+
+#[test]
+fn descending_is_non_increasing() {
+    for n in [0, 1, 2, 3, 100, 1000] {
+        for input in [
+            random_sequence(n, n as u64),
+            increasing_sequence(n),
+            few_unique_sequence(n, 3, 1),
+        ] {
+            let mut v = input.clone();
+            heap_sort_desc(&mut v);
+            assert!(v.windows(2).all(|w| w[0] >= w[1]), "{input:?}");
+        }
+    }
+}
+
+#[test]
+fn descending_is_a_permutation() {
+    let input = random_sequence(500, 7);
+    let mut v = input.clone();
+    heap_sort_desc(&mut v);
+    v.reverse();
+    let mut expected = input;
+    expected.sort();
+    assert_eq!(v, expected);
+}
+
+/// Number of comparisons `sort_fn` does on `input`, tallied in `counts`.
+fn comparisons<'a>(
+    sort_fn: fn(&mut [Counted<'a, usize>]),
+    input: &[usize],
+    counts: &'a Counts,
+) -> usize {
+    let mut v: Vec<_> = input.iter().map(|&x| Counted::new(x, counts)).collect();
+    sort_fn(&mut v);
+    counts.comparisons()
+}
+
+#[test]
+fn bottom_up_build_compares_less_than_top_down() {
+    let n = 10_000;
+    for input in [random_sequence(n, 1), increasing_sequence(n)] {
+        // building alone, without extracting any element, is linear
+        let build = comparisons(|v| heap_sort_partial(v, 0), &input, &Counts::new());
+        assert!(build < 2 * n, "{build} comparisons");
+
+        let bottom_up = comparisons(heap_sort, &input, &Counts::new());
+        let top_down = comparisons(heap_sort_topdown_build, &input, &Counts::new());
+        assert!(bottom_up < top_down, "{bottom_up} >= {top_down}");
+    }
+
+    // on increasing input every insertion moves up to the top of the heap, for
+    // about `n log2(n)` comparisons to build it, against less than `2 n`
+    let input = increasing_sequence(n);
+    let bottom_up = comparisons(heap_sort, &input, &Counts::new());
+    let top_down = comparisons(heap_sort_topdown_build, &input, &Counts::new());
+    assert!(top_down - bottom_up > 5 * n);
+}
+
+#[test]
+fn lazy_sort_yields_in_order() {
+    for n in [0, 1, 2, 3, 100, 1000] {
+        let input = random_sequence(n, n as u64);
+        let mut expected = input.clone();
+        expected.sort();
+        assert_eq!(sort_lazy(input).collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn lazy_sort_taking_only_the_first() {
+    let n = 1000;
+    let input = random_sequence(n, 1);
+    let mut expected = input.clone();
+    expected.sort();
+
+    let counts = Counts::new();
+    let v: Vec<_> = input.iter().map(|&x| Counted::new(x, &counts)).collect();
+    let first: Vec<usize> = sort_lazy(v).take(3).map(|x| x.value).collect();
+    assert_eq!(first, expected[..3]);
+    // building the heap and three removals, far from a full sort
+    assert!(counts.comparisons() < 3 * n, "{}", counts.comparisons());
+}
+
+#[test]
+fn partial_sorts_the_largest_then_continues() {
+    let n = 50;
+    let mut v = random_sequence(n, 3);
+    let mut expected = v.clone();
+    expected.sort();
+
+    heap_sort_partial(&mut v, 5);
+    assert_eq!(v[n - 5..], expected[n - 5..]);
+
+    // the rest is still a heap: extract the next 5 without building it again
+    heap_extract_largest(&mut v[..n - 5], 5);
+    assert_eq!(v[n - 10..], expected[n - 10..]);
+
+    v.sort();
+    assert_eq!(v, expected, "the elements are all still there");
+}
+
+#[test]
+fn partial_with_any_count() {
+    for n in [0, 1, 2, 3, 50] {
+        for count in [0, 1, n, n + 3] {
+            let mut v = random_sequence(n, n as u64);
+            let mut expected = v.clone();
+            expected.sort();
+            heap_sort_partial(&mut v, count);
+            let sorted = count.min(n);
+            assert_eq!(v[n - sorted..], expected[n - sorted..], "n = {n}");
+        }
+    }
+}
+
+#[test]
+fn sort_indexed_over_a_vec_matches_heap_sort() {
+    use std::cell::RefCell;
+
+    for n in [0, 1, 2, 3, 100, 1000] {
+        // few distinct keys tagged with their index, to tell equal keys apart
+        let input: Vec<(usize, usize)> = few_unique_sequence(n, 10, n as u64)
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+
+        let cell = RefCell::new(input.clone());
+        sort_indexed(
+            n,
+            |i, j| cell.borrow()[i].0 < cell.borrow()[j].0,
+            |i, j| cell.borrow_mut().swap(i, j),
+        );
+
+        // the same swaps as `heap_sort` comparing the keys, ties included
+        let mut expected = input;
+        heap_sort_by_key(&mut expected, |x| x.0);
+        assert_eq!(cell.into_inner(), expected, "n = {n}");
+    }
+}
+
+#[test]
+fn partial_sort_largest_matches_full_sort_tail() {
+    for n in [0, 1, 2, 3, 100, 1000] {
+        for k in [0, 1, 10, n, n + 5] {
+            let input = few_unique_sequence(n, 50, n as u64);
+            let mut expected = input.clone();
+            expected.sort();
+
+            let mut v = input;
+            partial_sort_largest(&mut v, k);
+            let k = k.min(n);
+            assert_eq!(v[n - k..], expected[n - k..], "n = {n}, k = {k}");
+
+            v.sort();
+            assert_eq!(v, expected, "the elements are all still there");
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Keeping a sorted slice sorted after editing an element.
+
+use sort::*;
+
+/// Sort `v`, replace `v[edited_index]` with `value`, restore the order with
+/// `resort_after_edit` and check it against sorting again.
+fn check_edit(mut v: Vec<u32>, edited_index: usize, value: u32) {
+    v.sort();
+    v[edited_index] = value;
+    let mut expected = v.clone();
+    expected.sort();
+    resort_after_edit(&mut v, edited_index);
+    assert_eq!(v, expected, "v[{edited_index}] = {value}");
+}
+
+#[test]
+fn edited_element_moves_down_or_up() {
+    let v = vec![10, 20, 30, 40, 50];
+    check_edit(v.clone(), 3, 5);
+    check_edit(v.clone(), 1, 55);
+    check_edit(v.clone(), 2, 30);
+    check_edit(v.clone(), 0, 60);
+    check_edit(v.clone(), 4, 0);
+    check_edit(vec![7], 0, 3);
+}
+
+#[test]
+fn every_edit_of_a_small_slice() {
+    let v = vec![1, 2, 2, 4, 4, 4, 7];
+    for i in 0..v.len() {
+        for value in 0..9 {
+            check_edit(v.clone(), i, value);
+        }
+    }
+}
+
+#[test]
+fn only_elements_in_between_move() {
+    let mut v = [(1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e')];
+    v[3] = (2, 'x');
+    resort_after_edit(&mut v, 3);
+    // `(3, 'c')` shifts up to make room, the others stay where they were
+    assert_eq!(v, [(1, 'a'), (2, 'b'), (2, 'x'), (3, 'c'), (5, 'e')]);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn rejects_index_out_of_bounds() {
+    resort_after_edit(&mut [1, 2], 2);
+}
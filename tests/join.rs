@@ -0,0 +1,62 @@
+//! Sort-merge join of two slices of key-value pairs.
+#![cfg(all(feature = "fastrand", feature = "std"))]
+
+use sort::*;
+
+#[test]
+fn duplicate_keys_give_the_cross_product() {
+    let mut left = vec![(2, 'a'), (1, 'b'), (2, 'c'), (3, 'd')];
+    let mut right = vec![(2, "x"), (4, "y"), (2, "z"), (1, "w")];
+    let mut joined = vec![];
+    sort_merge_join(&mut left, &mut right, |k, l, r| joined.push((*k, *l, *r)));
+
+    // equal keys come out in any order within their groups
+    joined.sort();
+    assert_eq!(
+        joined,
+        [
+            (1, 'b', "w"),
+            (2, 'a', "x"),
+            (2, 'a', "z"),
+            (2, 'c', "x"),
+            (2, 'c', "z"),
+        ]
+    );
+}
+
+#[test]
+fn no_matches() {
+    let mut left = vec![(1, ()), (3, ())];
+    let mut right: Vec<(i32, ())> = vec![(2, ()), (4, ())];
+    let mut calls = 0;
+    sort_merge_join(&mut left, &mut right, |_, _, _| calls += 1);
+    assert_eq!(calls, 0);
+
+    sort_merge_join(&mut left, &mut [], |_, _, _: &()| calls += 1);
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn group_sizes_multiply() {
+    let mut left: Vec<(u32, usize)> = (0..30).map(|i| (i % 3, i as usize)).collect();
+    let mut right: Vec<(u32, usize)> = (0..20).map(|i| (i % 4, i as usize)).collect();
+    let mut counts = [0; 4];
+    sort_merge_join(&mut left, &mut right, |&k, l, r| {
+        assert_eq!((*l % 3, *r % 4), (k as usize, k as usize));
+        counts[k as usize] += 1;
+    });
+    // keys 0, 1 and 2 appear 10 times on the left and 5 on the right
+    assert_eq!(counts, [50, 50, 50, 0]);
+}
+
+#[test]
+fn keys_need_not_be_cloned() {
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Key(u32);
+
+    let mut left = vec![(Key(2), 'a'), (Key(1), 'b')];
+    let mut right = vec![(Key(1), 'x'), (Key(2), 'y'), (Key(3), 'z')];
+    let mut joined = vec![];
+    sort_merge_join(&mut left, &mut right, |k, l, r| joined.push((k.0, *l, *r)));
+    assert_eq!(joined, [(1, 'b', 'x'), (2, 'a', 'y')]);
+}
@@ -0,0 +1,286 @@
+//! Merge sort variants and the merging primitives they are built on.
+#![cfg(feature = "testutil")]
+
+use std::{cell::Cell, cmp::Ordering};
+
+use sort::{generators::*, *};
+
+#[test]
+fn progress_never_decreases_and_ends_at_one() {
+    for n in [0, 1, 2, 3, 100, 1000] {
+        let mut v = random_sequence(n, n as u64);
+        let mut fractions = vec![];
+        merge_sort_with_progress(&mut v, |f| fractions.push(f));
+
+        assert!(is_sorted(&v));
+        assert!(fractions.windows(2).all(|w| w[0] <= w[1]), "{fractions:?}");
+        assert!(fractions.iter().all(|f| (0.0..=1.0).contains(f)));
+        assert_eq!(fractions.last(), Some(&1.0));
+    }
+}
+
+#[test]
+fn progress_is_reported_after_each_pass() {
+    // 1000 elements take 10 passes, with runs of 2, 4, ..., 1024 elements
+    let mut v = random_sequence(1000, 1);
+    let mut fractions = vec![];
+    merge_sort_with_progress(&mut v, |f| fractions.push(f));
+    assert_eq!(fractions.len(), 10);
+    assert_eq!(fractions[0], 2.0 / 1000.0);
+}
+
+/// Number of comparisons `sort_fn` does on `input`, tallied in `counts`.
+fn comparisons<'a>(
+    sort_fn: fn(&mut [Counted<'a, usize>]),
+    input: &[usize],
+    counts: &'a Counts,
+) -> usize {
+    let mut v: Vec<_> = input.iter().map(|&x| Counted::new(x, counts)).collect();
+    sort_fn(&mut v);
+    assert!(v.windows(2).all(|w| w[0].value <= w[1].value));
+    counts.comparisons()
+}
+
+#[test]
+fn owned_merges_without_cloning() {
+    let counts = Counts::new();
+    let mut v: Vec<_> = random_sequence(1000, 3)
+        .into_iter()
+        .map(|x| Counted::new(x, &counts))
+        .collect();
+    merge_sort_owned(&mut v);
+    assert!(v.windows(2).all(|w| w[0].value <= w[1].value));
+    assert_eq!(counts.clones(), 0);
+
+    let mut w = v.clone();
+    counts.reset();
+    w.reverse();
+    merge_sort_top_down(&mut w);
+    assert!(counts.clones() > 0, "merge_sort_top_down clones");
+}
+
+#[test]
+fn adaptive_skips_merges_on_presorted_input() {
+    let n = 1000;
+    let sorted = increasing_sequence(n);
+    // a single comparison per merge, of the last of the left and first of the right
+    assert_eq!(
+        comparisons(merge_sort_top_down_adaptive, &sorted, &Counts::new()),
+        n - 1
+    );
+    assert!(comparisons(merge_sort_top_down, &sorted, &Counts::new()) > 4 * n);
+
+    let nearly = nearly_sorted_sequence(n, 5, 1);
+    assert!(
+        comparisons(merge_sort_top_down_adaptive, &nearly, &Counts::new())
+            < comparisons(merge_sort_top_down, &nearly, &Counts::new()) * 3 / 4
+    );
+
+    let random = random_sequence(n, 1);
+    assert!(
+        comparisons(merge_sort_top_down_adaptive, &random, &Counts::new())
+            <= comparisons(merge_sort_top_down, &random, &Counts::new()) + n
+    );
+}
+
+#[test]
+fn sort_split_unequal_and_empty_halves() {
+    for (n, m) in [(0, 0), (0, 5), (5, 0), (3, 40), (40, 3), (17, 17)] {
+        let mut a = random_sequence(n, 1);
+        let mut b = random_sequence(m, 2);
+        let mut expected: Vec<usize> = a.iter().chain(&b).copied().collect();
+        expected.sort();
+
+        sort_split(&mut a, &mut b);
+        assert_eq!((a.len(), b.len()), (n, m));
+        assert_eq!(a.iter().chain(&b).copied().collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn merge_copy_matches_element_wise_merge() {
+    for n in [0, 1, 2, 3, 100, 1000] {
+        // few distinct keys, so merges copy long runs, tagged to check stability
+        let input: Vec<(usize, usize)> = few_unique_sequence(n, 5, n as u64)
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+
+        let mut expected = input.clone();
+        merge_sort_top_down_by(&mut expected, |a, b| a.0.cmp(&b.0));
+        let mut v = input;
+        merge_sort_copy_by(&mut v, |a, b| a.0.cmp(&b.0));
+        assert_eq!(v, expected, "n = {n}");
+    }
+}
+
+#[test]
+fn merge_copy_compares_once_per_element() {
+    // runs of the same half copied at once take no extra comparisons at their
+    // boundaries, so as many as merging element by element
+    for input in [
+        few_unique_sequence(1000, 5, 1),
+        random_sequence(1000, 1),
+        sawtooth_sequence(1000, 100),
+    ] {
+        let copy_count = Cell::new(0);
+        merge_sort_copy_by(&mut input.clone(), counting(&copy_count));
+        let count = Cell::new(0);
+        merge_sort_top_down_by(&mut input.clone(), counting(&count));
+        assert_eq!(copy_count.get(), count.get());
+    }
+}
+
+/// Ordered by `key` only, so `id` tells equal keys apart.
+#[derive(Debug, Clone, Copy, Default)]
+struct Record {
+    key: usize,
+    id: usize,
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Record {}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[test]
+fn merge_runs_with_any_buffer() {
+    for n in [0usize, 1, 2, 3, 5, 50, 300] {
+        for mid in [0, 1, n / 3, n / 2, n - n / 3, n.saturating_sub(1), n] {
+            let mid = mid.min(n);
+            let mut keys = few_unique_sequence(n, 7, (n + mid) as u64);
+            keys[..mid].sort();
+            keys[mid..].sort();
+            let input: Vec<Record> = keys
+                .into_iter()
+                .enumerate()
+                .map(|(id, key)| Record { key, id })
+                .collect();
+
+            // sorting the ids within equal keys is the stable merge
+            let mut expected = input.clone();
+            expected.sort_by_key(|r| (r.key, r.id));
+
+            // a buffer for the shorter run, one too small for it, or none
+            let shorter = mid.min(n - mid);
+            for len in [n, shorter, shorter.saturating_sub(1), 1, 0] {
+                let mut v = input.clone();
+                merge_runs(&mut v, mid, &mut vec![Record::default(); len]);
+                let ids = |v: &[Record]| v.iter().map(|r| (r.key, r.id)).collect::<Vec<_>>();
+                assert_eq!(ids(&v), ids(&expected), "n = {n}, mid = {mid}, buf = {len}");
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn merge_runs_rejects_mid_past_the_end() {
+    merge_runs(&mut [1, 2], 3, &mut []);
+}
+
+/// Merge `src` into a copy of `dst` with `merge_into_with_capacity`, with room
+/// reserved up front, and check the result against sorting both.
+fn check_merge_into(dst: &[Record], src: &[Record]) {
+    let mut merged = Vec::with_capacity(dst.len() + src.len());
+    merged.extend_from_slice(dst);
+    let capacity = merged.capacity();
+    merge_into_with_capacity(&mut merged, dst.len(), src);
+    assert_eq!(merged.capacity(), capacity, "no reallocation");
+
+    // equal keys from `dst` come before those from `src`
+    let mut expected: Vec<Record> = dst.iter().chain(src).copied().collect();
+    expected.sort_by_key(|r| r.key);
+    let ids = |v: &[Record]| v.iter().map(|r| (r.key, r.id)).collect::<Vec<_>>();
+    assert_eq!(ids(&merged), ids(&expected));
+}
+
+#[test]
+fn merge_into_with_capacity_edge_cases() {
+    let records = |keys: &[usize], first_id: usize| -> Vec<Record> {
+        keys.iter()
+            .enumerate()
+            .map(|(i, &key)| Record {
+                key,
+                id: first_id + i,
+            })
+            .collect()
+    };
+    let evens = records(&[0, 2, 4, 6, 8], 0);
+    let odds = records(&[1, 3, 5, 7, 9], 100);
+
+    check_merge_into(&evens, &[]);
+    check_merge_into(&[], &odds);
+    check_merge_into(&[], &[]);
+    check_merge_into(&evens, &odds);
+    check_merge_into(&odds, &evens);
+    check_merge_into(&evens, &records(&[2, 2, 6], 100));
+    check_merge_into(&records(&[10, 11], 0), &evens);
+}
+
+#[test]
+fn merge_into_with_capacity_discards_padding() {
+    let mut dst = vec![1, 4, 7, 0, 0, 0];
+    merge_into_with_capacity(&mut dst, 3, &[2, 3, 9]);
+    assert_eq!(dst, [1, 2, 3, 4, 7, 9]);
+}
+
+#[test]
+fn merge_segments_of_different_sizes() {
+    let mut v = vec![3, 8, 9, 1, 2, 5, 7, 11, 0, 4];
+    merge_segments(&mut v, &[3, 8]);
+    assert_eq!(v, [0, 1, 2, 3, 4, 5, 7, 8, 9, 11]);
+
+    // empty segments, and a boundary at `0` or at the end, change nothing
+    let mut v = vec![4, 6, 1, 5];
+    merge_segments(&mut v, &[0, 2, 2, 4]);
+    assert_eq!(v, [1, 4, 5, 6]);
+
+    let mut v: Vec<u32> = vec![];
+    merge_segments(&mut v, &[]);
+    assert!(v.is_empty());
+}
+
+#[test]
+fn merge_segments_is_stable() {
+    let keys = [1, 3, 3, 5, 1, 3, 4, 0, 3, 5];
+    let mut v: Vec<Record> = keys
+        .iter()
+        .enumerate()
+        .map(|(id, &key)| Record { key, id })
+        .collect();
+    let mut expected = v.clone();
+    expected.sort_by_key(|r| (r.key, r.id));
+
+    merge_segments(&mut v, &[4, 7]);
+    let ids = |v: &[Record]| v.iter().map(|r| r.id).collect::<Vec<_>>();
+    assert_eq!(ids(&v), ids(&expected));
+}
+
+#[test]
+#[should_panic(expected = "increasing order")]
+fn merge_segments_rejects_unordered_boundaries() {
+    merge_segments(&mut [1, 2, 0], &[2, 1]);
+}
+
+#[test]
+#[should_panic(expected = "within `v`")]
+fn merge_segments_rejects_boundary_past_the_end() {
+    merge_segments(&mut [1, 2], &[3]);
+}
@@ -0,0 +1,36 @@
+//! Insertion sort with a budget of element moves.
+
+use sort::*;
+
+#[test]
+fn generous_budget_sorts() {
+    let mut v: Vec<u32> = (0..20).rev().collect();
+    // inserting the `i`-th element of reversed input moves `i + 1` elements
+    let needed = (1..20).map(|i| i + 1).sum();
+    assert!(sort_move_capped(&mut v, needed));
+    assert!(is_sorted(&v));
+
+    let mut sorted: Vec<u32> = (0..20).collect();
+    assert!(
+        sort_move_capped(&mut sorted, 0),
+        "sorted input moves nothing"
+    );
+}
+
+#[test]
+fn tiny_budget_stops_early() {
+    let mut v: Vec<u32> = (0..20).rev().collect();
+    let needed: usize = (1..20).map(|i| i + 1).sum();
+    assert!(!sort_move_capped(&mut v, needed - 1));
+    assert!(!is_sorted(&v));
+
+    // stopped before the insertion over budget: 2 + 3 + 4 moves fit in 10
+    let mut v: Vec<u32> = (0..20).rev().collect();
+    assert!(!sort_move_capped(&mut v, 10));
+    assert_eq!(v[..4], [16, 17, 18, 19], "the prefix is sorted");
+    assert_eq!(
+        v[4..],
+        (0..16).rev().collect::<Vec<_>>(),
+        "the rest untouched"
+    );
+}
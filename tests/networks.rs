@@ -0,0 +1,37 @@
+//! Sorting networks built by `build_network`, checked with the 0/1 principle:
+//! a comparator network sorts every input if it sorts every input of zeros
+//! and ones.
+#![cfg(feature = "alloc")]
+
+use sort::*;
+
+#[test]
+fn sorts_all_binary_inputs() {
+    for n in 0..=12 {
+        let network = build_network(n);
+        for bits in 0u32..1 << n {
+            let mut v: Vec<u32> = (0..n).map(|i| (bits >> i) & 1).collect();
+            apply_network(&mut v, &network);
+            assert!(is_sorted(&v), "n = {n}, input = {bits:0n$b}");
+        }
+    }
+}
+
+#[test]
+fn comparators_are_in_range_and_ordered() {
+    for n in 0..=33 {
+        for (i, j) in build_network(n) {
+            assert!(i < j && j < n, "n = {n}: ({i}, {j})");
+        }
+    }
+}
+
+#[test]
+fn known_sizes() {
+    assert!(build_network(0).is_empty());
+    assert!(build_network(1).is_empty());
+    assert_eq!(build_network(2), [(0, 1)]);
+    // Batcher's network for 4 elements has 5 comparators, for 8 it has 19
+    assert_eq!(build_network(4).len(), 5);
+    assert_eq!(build_network(8).len(), 19);
+}
@@ -0,0 +1,32 @@
+//! Sorting into pages of a fixed size.
+#![cfg(feature = "alloc")]
+
+use sort::*;
+
+#[test]
+fn last_page_holds_the_remainder() {
+    let mut v = vec![5, 4, 3, 2, 1, 0, 9];
+    assert_eq!(sort_into_pages(&mut v, 3), [0..3, 3..6, 6..7]);
+    assert_eq!(v, [0, 1, 2, 3, 4, 5, 9]);
+}
+
+#[test]
+fn pages_are_contiguous_and_cover_the_input() {
+    for n in [0, 1, 9, 10, 11, 100] {
+        for page_size in [1, 3, 10, 200] {
+            let mut v: Vec<usize> = (0..n).rev().collect();
+            let pages = sort_into_pages(&mut v, page_size);
+            assert_eq!(pages.len(), n.div_ceil(page_size));
+            assert_eq!(pages.first().map_or(0, |p| p.start), 0);
+            assert_eq!(pages.last().map_or(0, |p| p.end), n);
+            assert!(pages.windows(2).all(|w| w[0].end == w[1].start));
+            assert!(pages.iter().all(|p| p.len() <= page_size));
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "must not be zero")]
+fn rejects_zero_page_size() {
+    sort_into_pages(&mut [1, 2], 0);
+}
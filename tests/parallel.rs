@@ -0,0 +1,71 @@
+//! Parallel sorts, on inputs large enough to be split across threads.
+#![cfg(all(feature = "testutil", feature = "std"))]
+
+use sort::{generators::*, *};
+
+/// Inputs from this length on are sorted in parallel.
+const PARALLEL_LEN: usize = 1 << 14;
+
+#[test]
+fn sample_sort_large_random() {
+    for n in [PARALLEL_LEN, PARALLEL_LEN + 1, 300_000] {
+        let mut v = random_sequence(n, n as u64);
+        let mut expected = v.clone();
+        expected.sort();
+        sample_sort(&mut v);
+        assert_eq!(v, expected, "n = {n}");
+    }
+}
+
+#[test]
+fn sample_sort_large_few_unique() {
+    // most buckets end up empty when the splitters are all equal
+    for unique in [1, 2, 5] {
+        let mut v = few_unique_sequence(100_000, unique, 1);
+        let mut expected = v.clone();
+        expected.sort();
+        sample_sort(&mut v);
+        assert_eq!(v, expected, "unique = {unique}");
+    }
+}
+
+#[cfg(feature = "rayon")]
+mod par_quick_sort {
+    use super::*;
+
+    #[test]
+    fn large_random() {
+        for n in [PARALLEL_LEN, PARALLEL_LEN + 1, 300_000] {
+            let mut v = random_sequence(n, n as u64);
+            let mut expected = v.clone();
+            expected.sort();
+            par_quick_sort(&mut v);
+            assert_eq!(v, expected, "n = {n}");
+        }
+    }
+
+    #[test]
+    fn large_few_unique() {
+        // the three-way partition puts all the copies of the pivot in place
+        for unique in [1, 2, 5] {
+            let mut v = few_unique_sequence(100_000, unique, 1);
+            let mut expected = v.clone();
+            expected.sort();
+            par_quick_sort(&mut v);
+            assert_eq!(v, expected, "unique = {unique}");
+        }
+    }
+
+    #[test]
+    fn inside_a_custom_pool() {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        let mut v = random_sequence(100_000, 7);
+        let mut expected = v.clone();
+        expected.sort();
+        pool.install(|| par_quick_sort(&mut v));
+        assert_eq!(v, expected);
+    }
+}
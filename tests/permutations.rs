@@ -0,0 +1,155 @@
+//! Applying permutations in place, and the sorts built on sorting indices and
+//! permuting the elements once.
+#![cfg(feature = "alloc")]
+
+use core::cmp::Ordering;
+
+use sort::*;
+
+/// An element expensive to move, ordered by `key` only.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Large {
+    key: u32,
+    payload: [u64; 32],
+}
+
+impl Large {
+    fn new(key: u32) -> Self {
+        Large {
+            key,
+            payload: [key as u64; 32],
+        }
+    }
+}
+
+#[test]
+fn applies_known_permutation() {
+    let mut v = vec!['a', 'b', 'c', 'd', 'e'];
+    let mut perm = vec![2, 0, 1, 4, 3];
+    apply_permutation_in_place(&mut v, &mut perm);
+    assert_eq!(v, ['c', 'a', 'b', 'e', 'd']);
+    assert_eq!(perm, [0, 1, 2, 3, 4], "`perm` is left as the identity");
+}
+
+#[test]
+fn applies_every_permutation_of_four() {
+    let original = [10, 20, 30, 40];
+    for a in 0..4 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let d = 6 - a - b - c;
+                let perm = [a, b, c, d];
+                let mut v = original;
+                apply_permutation_in_place(&mut v, &mut perm.clone());
+                assert_eq!(v, perm.map(|i| original[i]), "perm = {perm:?}");
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "not a permutation")]
+fn rejects_repeated_index() {
+    apply_permutation_in_place(&mut [1, 2], &mut [1, 1]);
+}
+
+#[test]
+#[should_panic(expected = "same length")]
+fn rejects_wrong_length() {
+    apply_permutation_in_place(&mut [1, 2, 3], &mut [0, 1]);
+}
+
+#[test]
+fn minimize_moves_swaps_n_minus_cycles() {
+    // the sorting permutation has cycles (0 1 2), (3 4) and (5)
+    let mut v: Vec<Large> = [1, 2, 0, 4, 3, 5].map(Large::new).to_vec();
+    assert_eq!(sort_minimize_moves(&mut v), 6 - 3);
+    assert_eq!(v, [0, 1, 2, 3, 4, 5].map(Large::new));
+
+    let mut sorted: Vec<Large> = (0..10).map(Large::new).collect();
+    assert_eq!(sort_minimize_moves(&mut sorted), 0);
+
+    // reversing an even length is `n / 2` cycles of two elements
+    let mut reversed: Vec<Large> = (0..10).rev().map(Large::new).collect();
+    assert_eq!(sort_minimize_moves(&mut reversed), 5);
+    assert_eq!(reversed, (0..10).map(Large::new).collect::<Vec<_>>());
+}
+
+#[test]
+fn check_permutation_of_indices() {
+    let mut v = [2, 0, 3, 1];
+    assert!(sort_check_permutation(&mut v));
+    assert_eq!(v, [0, 1, 2, 3]);
+
+    assert!(sort_check_permutation(&mut []));
+    assert!(!sort_check_permutation(&mut [2, 0, 0]), "duplicate");
+    assert!(!sort_check_permutation(&mut [3, 0, 1]), "out of range");
+}
+
+/// Ordered by `key` only, so equal keys can still be told apart by `tag`.
+#[derive(Debug, Clone, Copy)]
+struct Tagged {
+    key: u8,
+    tag: u32,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Tagged {}
+
+impl PartialOrd for Tagged {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tagged {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[test]
+fn restore_undoes_sort_exactly() {
+    for n in [0, 1, 2, 3, 100] {
+        let original: Vec<Tagged> = (0..n)
+            .map(|i| Tagged {
+                key: ((i * 7919) % 11) as u8,
+                tag: i,
+            })
+            .collect();
+        let mut v = original.clone();
+        let undo = sort_with_undo(&mut v);
+        assert!(v.windows(2).all(|w| w[0].key <= w[1].key));
+
+        restore(&mut v, &undo);
+        let pairs = |v: &[Tagged]| v.iter().map(|x| (x.key, x.tag)).collect::<Vec<_>>();
+        assert_eq!(pairs(&v), pairs(&original), "n = {n}");
+    }
+}
+
+#[test]
+fn min_writes_skips_elements_in_place() {
+    // two cycles of two, `2` already in place
+    let mut v = [1, 0, 2, 4, 3].map(Large::new);
+    assert_eq!(sort_min_writes(&mut v), 4);
+    assert_eq!(v, [0, 1, 2, 3, 4].map(Large::new));
+
+    let mut sorted: Vec<Large> = (0..10).map(Large::new).collect();
+    assert_eq!(sort_min_writes(&mut sorted), 0);
+
+    // every element out of place is written exactly once
+    let input: Vec<u32> = (0..100).map(|i| (i * 37) % 100).collect();
+    let out_of_place = input
+        .iter()
+        .enumerate()
+        .filter(|&(i, &x)| i as u32 != x)
+        .count();
+    let mut v: Vec<Large> = input.into_iter().map(Large::new).collect();
+    assert_eq!(sort_min_writes(&mut v), out_of_place);
+    assert_eq!(v, (0..100).map(Large::new).collect::<Vec<_>>());
+}
@@ -0,0 +1,88 @@
+//! The operations recorded by `plan_sort`, checked by replaying them.
+#![cfg(feature = "testutil")]
+
+use sort::{generators::*, *};
+
+/// Apply the swaps and moves of `ops` to a copy of `v`.
+fn replay(v: &[usize], ops: &[Op]) -> Vec<usize> {
+    let mut v = v.to_vec();
+    for op in ops {
+        match *op {
+            Op::Compare(..) => {}
+            Op::Swap(i, j) => v.swap(i, j),
+            Op::Move(from, to) if from > to => v[to..=from].rotate_right(1),
+            Op::Move(from, to) => v[from..=to].rotate_left(1),
+        }
+    }
+    v
+}
+
+#[test]
+fn replaying_the_plan_sorts() {
+    for algorithm in ["bubble", "insertion", "selection", "heap"] {
+        for n in [0, 1, 2, 3, 10, 50] {
+            let v = few_unique_sequence(n, 20, n as u64);
+            let original = v.clone();
+            let ops = plan_sort(&v, algorithm);
+            assert_eq!(v, original, "the input is not touched");
+
+            let mut expected = v.clone();
+            expected.sort();
+            assert_eq!(replay(&v, &ops), expected, "{algorithm}, n = {n}");
+        }
+    }
+}
+
+#[test]
+fn exact_operations_on_a_tiny_input() {
+    assert_eq!(
+        plan_sort(&[3, 2, 1], "bubble"),
+        [
+            Op::Compare(0, 1),
+            Op::Swap(0, 1),
+            Op::Compare(1, 2),
+            Op::Swap(1, 2),
+            Op::Compare(0, 1),
+            Op::Swap(0, 1),
+        ]
+    );
+    assert_eq!(
+        plan_sort(&[3, 2, 1], "insertion"),
+        [
+            Op::Compare(0, 1),
+            Op::Move(1, 0),
+            Op::Compare(1, 2),
+            Op::Compare(0, 2),
+            Op::Move(2, 0),
+        ]
+    );
+    // `1` is swapped into place, and then `2` already is
+    assert_eq!(
+        plan_sort(&[3, 2, 1], "selection"),
+        [
+            Op::Compare(1, 0),
+            Op::Compare(2, 1),
+            Op::Swap(0, 2),
+            Op::Compare(2, 1),
+        ]
+    );
+    // already a heap, so its root `3` is swapped to the end, `1` sifted down
+    // past `2`, and `2` swapped to the end
+    assert_eq!(
+        plan_sort(&[3, 2, 1], "heap"),
+        [
+            Op::Compare(2, 1),
+            Op::Compare(1, 0),
+            Op::Swap(0, 2),
+            Op::Compare(1, 0),
+            Op::Swap(0, 1),
+            Op::Swap(0, 1),
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn rejects_unknown_algorithm() {
+    plan_sort(&[1, 2], "bogo");
+}
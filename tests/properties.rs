@@ -0,0 +1,164 @@
+//! Every sort, on many random inputs, gives the same result as the native sort,
+//! so its output is sorted and a permutation of its input, both on the inputs
+//! and on their elements wrapped in `Reverse`, sorted in decreasing order.
+//! proptest shrinks a failing input to a minimal one before reporting it.
+#![cfg(all(feature = "std", feature = "fastrand"))]
+
+use std::cmp::Reverse;
+
+use proptest::prelude::*;
+use sort::*;
+
+/// Number of random inputs tried on each sort.
+const CASES: u32 = 500;
+
+/// A random input of up to 100 elements, drawn from a range that is sometimes
+/// small, for many equal elements, and sometimes all of `i32`.
+fn random_input() -> impl Strategy<Value = Vec<i32>> {
+    prop_oneof![Just(1), Just(4), Just(100), Just(i32::MAX)]
+        .prop_flat_map(|max| prop::collection::vec(-max..=max, 0..=100))
+}
+
+/// Expands to checking that each given function sorts `input`, and `input`
+/// wrapped in `Reverse`, as the native sort does, failing with the name of the
+/// first that does not.
+macro_rules! check_sorts {
+    ( $input:expr , $( $sort_fn:expr ),+ $( , )? ) => {
+        let mut expected = $input.clone();
+        expected.sort();
+        let reversed: Vec<Reverse<i32>> = $input.iter().copied().map(Reverse).collect();
+        let mut expected_reversed = reversed.clone();
+        expected_reversed.sort();
+        $(
+            let mut v = $input.clone();
+            $sort_fn(&mut v);
+            prop_assert_eq!(&v, &expected, "{}", stringify!($sort_fn));
+            let mut v = reversed.clone();
+            $sort_fn(&mut v);
+            prop_assert_eq!(&v, &expected_reversed, "{} on Reverse", stringify!($sort_fn));
+        )+
+    };
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn simple_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            gnome_sort,
+            bubble_sort,
+            cocktail_sort,
+            odd_even_sort,
+            comb_sort,
+            insertion_sort,
+            selection_sort,
+            |v: &mut [_]| selection_sort_opts(v, true),
+            pancake_sort,
+            shell_sort,
+            insertion_sort_desc_reversed,
+        );
+    }
+
+    #[test]
+    fn quick_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            quick_sort,
+            quick_sort_3,
+            |v: &mut [_]| quick_sort_seeded(v, 7),
+            |v: &mut [_]| quick_sort_3_seeded(v, 7),
+            |v: &mut [_]| quick_sort_sampled_median(v, 3),
+            quick_sort_hinted,
+            |v: &mut [_]| quick_sort_depth_cutoff(v, 3),
+            |v: &mut [_]| quick_sort_by_key(v, |x| *x),
+            intro_sort,
+            sort,
+            quick_sort_median_of_3,
+            quick_sort_median_of_medians,
+        );
+    }
+
+    #[test]
+    fn heap_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            heap_sort,
+            smooth_sort,
+            heap_sort_topdown_build,
+            |v: &mut [_]| partial_sort(v, v.len()),
+        );
+    }
+
+    #[test]
+    fn merge_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            merge_sort_top_down,
+            merge_sort_copy,
+            merge_sort_top_down_insert,
+            merge_sort_top_down_adaptive,
+            merge_sort_bottom_up,
+            merge_sort_bottom_up_insert,
+            merge_sort_blocked,
+            merge_sort_in_place,
+            merge_sort_owned,
+            merge_sort_parallel_stable,
+            |v: &mut [_]| merge_sort_with_buffer(v, &mut vec![]),
+            tim_sort,
+        );
+    }
+
+    #[test]
+    fn by_key_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            |v: &mut [_]| cocktail_sort_by_key(v, |x| *x),
+            |v: &mut [_]| odd_even_sort_by_key(v, |x| *x),
+            |v: &mut [_]| comb_sort_by_key(v, |x| *x),
+            |v: &mut [_]| pancake_sort_by_key(v, |x| *x),
+            |v: &mut [_]| intro_sort_by_key(v, |x| *x),
+            |v: &mut [_]| smooth_sort_by_key(v, |x| *x),
+            |v: &mut [_]| tim_sort_by_key(v, |x| *x),
+            |v: &mut [_]| merge_sort_in_place_by_key(v, |x| *x),
+        );
+    }
+
+    #[test]
+    fn other_sorts_match_native(input in random_input()) {
+        check_sorts!(
+            input,
+            bitonic_sort_padded,
+            sample_sort,
+            sort_interned,
+            sort_reverse_reversed,
+            |v: &mut [_]| {
+                sort_minimize_moves(v);
+            },
+        );
+    }
+}
+
+/// [`insertion_sort_desc`] reversed, to compare it with increasing order.
+fn insertion_sort_desc_reversed<T: Ord>(v: &mut [T]) {
+    insertion_sort_desc(v);
+    v.reverse();
+}
+
+/// [`sort_reverse`] reversed, to compare it with increasing order.
+fn sort_reverse_reversed<T: Ord>(v: &mut [T]) {
+    sort_reverse(v);
+    v.reverse();
+}
+
+/// [`bitonic_sort`] of `v` padded with its largest element up to a power of
+/// two length.
+fn bitonic_sort_padded<T: Ord + Clone>(v: &mut Vec<T>) {
+    let n = v.len();
+    if let Some(max) = v.iter().max().cloned() {
+        v.resize(n.next_power_of_two(), max);
+    }
+    bitonic_sort(v);
+    v.truncate(n);
+}
@@ -0,0 +1,318 @@
+//! Quicksort variants, and binary Quicksort on the degenerate partitions: all
+//! elements equal to the pivot, and pivots landing on either end of the
+//! sub-array.
+
+use sort::*;
+
+/// Always picks the pivot at the same end of the sub-array.
+struct EndPivot {
+    last: bool,
+}
+
+impl PivotRng for EndPivot {
+    fn index(&mut self, n: usize) -> usize {
+        if self.last {
+            n - 1
+        } else {
+            0
+        }
+    }
+}
+
+/// Sort `v` with both end pivots and the middle one, and check against the
+/// native sort.
+fn check(input: &[usize]) {
+    let mut expected = input.to_vec();
+    expected.sort();
+    for last in [false, true] {
+        let mut v = input.to_vec();
+        quick_sort_with_rng(&mut v, &mut EndPivot { last });
+        assert_eq!(v, expected, "last = {last}");
+    }
+
+    let mut v = input.to_vec();
+    quick_sort_with_rng(&mut v, &mut MiddlePivot);
+    assert_eq!(v, expected, "middle");
+}
+
+#[test]
+fn all_equal() {
+    check(&[7; 1000]);
+}
+
+#[test]
+fn sorted_pivot_is_smallest_or_largest() {
+    check(&(0..1000).collect::<Vec<_>>());
+    check(&(0..1000).rev().collect::<Vec<_>>());
+}
+
+#[test]
+fn few_distinct_values() {
+    check(&(0..1000).map(|i| i % 3).collect::<Vec<_>>());
+}
+
+mod depth_cutoff {
+    use super::*;
+
+    /// A scrambled input with repeats.
+    fn scrambled(n: usize) -> Vec<usize> {
+        (0..n).map(|i| (i * 7919) % (n / 3 + 1)).collect()
+    }
+
+    #[test]
+    fn sorts_at_every_depth() {
+        for max_depth in [0, 1, 2, 5, 100, usize::MAX] {
+            for n in [0, 1, 2, 3, 100, 1000] {
+                let mut v = scrambled(n);
+                quick_sort_depth_cutoff_with_rng(&mut v, max_depth, &mut MiddlePivot);
+                assert!(is_sorted(&v), "max_depth = {max_depth}, n = {n}");
+
+                let mut v = scrambled(n);
+                let mut rng = EndPivot { last: true };
+                quick_sort_depth_cutoff_with_rng(&mut v, max_depth, &mut rng);
+                assert!(is_sorted(&v), "max_depth = {max_depth}, n = {n}, end pivot");
+            }
+        }
+    }
+
+    #[test]
+    fn depth_zero_is_insertion_sort() {
+        let counts = Counts::new();
+        let input: Vec<_> = scrambled(100)
+            .into_iter()
+            .map(|x| Counted::new(x, &counts))
+            .collect();
+
+        let mut v = input.clone();
+        counts.reset();
+        quick_sort_depth_cutoff_with_rng(&mut v, 0, &mut MiddlePivot);
+        let comparisons = counts.comparisons();
+
+        let mut w = input;
+        counts.reset();
+        insertion_sort(&mut w);
+        assert_eq!(comparisons, counts.comparisons());
+        assert!(is_sorted(&v));
+    }
+
+    #[test]
+    fn unbounded_depth_on_many_equal_elements() {
+        let mut v = vec![42; 1_000_000];
+        quick_sort_depth_cutoff_with_rng(&mut v, usize::MAX, &mut MiddlePivot);
+        assert!(v.iter().all(|&x| x == 42));
+
+        let mut v: Vec<usize> = (0..100_000).rev().collect();
+        quick_sort_depth_cutoff_with_rng(&mut v, usize::MAX, &mut MiddlePivot);
+        assert!(is_sorted(&v));
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod sampled_median {
+    use super::*;
+
+    #[test]
+    fn sorts_with_any_sample_size() {
+        let input: Vec<usize> = (0..1000).map(|i| (i * 7919) % 301).collect();
+        let mut expected = input.clone();
+        expected.sort();
+        for sample in [0, 1, 3, 5, 9, 2000] {
+            let mut v = input.clone();
+            quick_sort_sampled_median_with_rng(&mut v, sample, &mut MiddlePivot);
+            assert_eq!(v, expected, "sample = {sample}");
+        }
+    }
+
+    #[test]
+    fn median_of_medians_after_unbalanced_partition() {
+        // a sample of the first element of sorted input is always the smallest
+        let n = 10_000;
+        let counts = Counts::new();
+        let mut v: Vec<_> = (0..n).map(|x| Counted::new(x, &counts)).collect();
+        quick_sort_sampled_median_with_rng(&mut v, 1, &mut EndPivot { last: false });
+        assert!(v.windows(2).all(|w| w[0].value <= w[1].value));
+        assert!(
+            counts.comparisons() < n * n / 20,
+            "{} comparisons",
+            counts.comparisons()
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod trace {
+    use super::*;
+
+    #[test]
+    fn middle_pivot_partitions_of_sorted_input() {
+        let mut v: Vec<usize> = (0..64).collect();
+        // the halves of 32 and 31 elements after the first partition are
+        // still sorted, and only those longer than 30 are partitioned again,
+        // the larger last
+        assert_eq!(
+            quick_sort_trace_with_rng(&mut v, &mut MiddlePivot),
+            [(0, 64, 32), (33, 31, 48), (0, 32, 16)]
+        );
+        assert!(is_sorted(&v));
+    }
+
+    #[test]
+    fn pivots_split_their_sub_arrays() {
+        for n in [0, 1, 31, 100, 1000] {
+            let mut v: Vec<usize> = (0..n).map(|i| (i * 7919) % 97).collect();
+            let trace = quick_sort_trace_with_rng(&mut v, &mut EndPivot { last: true });
+            assert!(is_sorted(&v));
+            for (start, len, pivot) in trace {
+                assert!(start <= pivot && pivot < start + len && start + len <= n);
+                assert!(v[start..pivot].iter().all(|x| *x <= v[pivot]));
+                assert!(v[pivot..start + len].iter().all(|x| *x >= v[pivot]));
+            }
+        }
+    }
+}
+
+mod by_key {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Employee {
+        name: &'static str,
+        salary: u32,
+        bonus: u32,
+    }
+
+    #[test]
+    fn sorts_structs_by_computed_key() {
+        let mut v = vec![
+            Employee {
+                name: "ada",
+                salary: 120,
+                bonus: 10,
+            },
+            Employee {
+                name: "bob",
+                salary: 90,
+                bonus: 50,
+            },
+            Employee {
+                name: "cy",
+                salary: 100,
+                bonus: 0,
+            },
+            Employee {
+                name: "di",
+                salary: 80,
+                bonus: 5,
+            },
+        ];
+        let mut calls = 0;
+        quick_sort_by_key_with_rng(
+            &mut v,
+            |e| {
+                calls += 1;
+                e.salary + e.bonus
+            },
+            &mut MiddlePivot,
+        );
+        let names: Vec<_> = v.iter().map(|e| e.name).collect();
+        assert_eq!(names, ["di", "cy", "ada", "bob"]);
+        assert!(calls > v.len(), "keys are not cached");
+    }
+
+    #[test]
+    fn sorts_by_key_with_every_pivot() {
+        let input: Vec<(usize, usize)> = (0..1000).map(|i| (i, (i * 7919) % 101)).collect();
+        for last in [false, true] {
+            let mut v = input.clone();
+            quick_sort_by_key_with_rng(&mut v, |x| x.1, &mut EndPivot { last });
+            assert!(v.windows(2).all(|w| w[0].1 <= w[1].1), "last = {last}");
+        }
+    }
+}
+
+mod hinted {
+    use super::*;
+
+    /// [`MiddlePivot`] counting how many times it is asked.
+    struct CountingRng {
+        calls: usize,
+    }
+
+    impl PivotRng for CountingRng {
+        fn index(&mut self, n: usize) -> usize {
+            self.calls += 1;
+            n / 2
+        }
+    }
+
+    #[test]
+    fn balanced_partitions_need_no_random_hints() {
+        // the middle of sorted input splits it evenly, so only the top is random
+        let mut v: Vec<usize> = (0..10_000).collect();
+        let mut rng = CountingRng { calls: 0 };
+        quick_sort_hinted_with_rng(&mut v, &mut rng);
+        assert!(is_sorted(&v));
+        assert_eq!(rng.calls, 1);
+    }
+
+    #[test]
+    fn sorts_with_end_hints() {
+        let organ: Vec<usize> = (0..1000).map(|i: usize| i.min(999 - i)).collect();
+        let scrambled: Vec<usize> = (0..1000).map(|i| (i * 7919) % 301).collect();
+        for input in [organ, scrambled] {
+            let mut expected = input.clone();
+            expected.sort();
+            for last in [false, true] {
+                let mut v = input.clone();
+                quick_sort_hinted_with_rng(&mut v, &mut EndPivot { last });
+                assert_eq!(v, expected, "last = {last}");
+            }
+        }
+    }
+}
+
+mod balance_stats {
+    use super::*;
+
+    #[test]
+    fn middle_pivot_on_sorted_input_is_balanced() {
+        let n = 1 << 14;
+        let mut v: Vec<usize> = (0..n).collect();
+        let stats = quick_sort_balance_stats_with_rng(&mut v, &mut MiddlePivot);
+        assert!(is_sorted(&v));
+        // halving down to 30 elements takes about log2(n / 30) levels
+        assert!(stats.max_depth <= 10, "{stats:?}");
+        assert!(stats.worst_ratio > 0.45, "{stats:?}");
+    }
+
+    #[test]
+    fn end_pivot_on_sorted_input_is_lopsided() {
+        let n = 1000;
+        let mut v: Vec<usize> = (0..n).collect();
+        let stats = quick_sort_balance_stats_with_rng(&mut v, &mut EndPivot { last: false });
+        assert!(is_sorted(&v));
+        // every partition takes a single element off, down to 30 elements
+        assert_eq!(stats.worst_ratio, 0.0);
+        assert_eq!(stats.partitions, n - 30);
+        assert_eq!(stats.max_depth, n - 30);
+    }
+
+    #[test]
+    fn no_partitions_for_small_input() {
+        let mut v = [3, 1, 2];
+        let stats = quick_sort_balance_stats_with_rng(&mut v, &mut MiddlePivot);
+        assert_eq!(stats.partitions, 0);
+        assert_eq!(stats.worst_ratio, 0.5);
+    }
+}
+
+#[cfg(all(feature = "fastrand", feature = "std"))]
+#[test]
+fn random_pivot_depth_is_logarithmic() {
+    let n = 100_000;
+    let mut v: Vec<usize> = (0..n).map(|i| (i * 7919) % n).collect();
+    let stats = quick_sort_balance_stats(&mut v);
+    assert!(is_sorted(&v));
+    // log2(n) is 17, random pivots rarely go more than a few times deeper
+    assert!(stats.max_depth < 4 * 17, "{stats:?}");
+}
@@ -0,0 +1,43 @@
+//! Ranks of elements among the others, with ties in both ranking styles.
+#![cfg(feature = "alloc")]
+
+use sort::*;
+
+#[test]
+fn competition_ranks_skip_after_ties() {
+    assert_eq!(rank(&[30, 10, 20, 20]), [3, 0, 1, 1]);
+    assert_eq!(rank(&[30, 10, 20, 20, 5, 30]), [4, 1, 2, 2, 0, 4]);
+    assert_eq!(rank(&[7, 7, 7]), [0, 0, 0]);
+}
+
+#[test]
+fn dense_ranks_do_not_skip_after_ties() {
+    assert_eq!(dense_rank(&[30, 10, 20, 20]), [2, 0, 1, 1]);
+    assert_eq!(dense_rank(&[30, 10, 20, 20, 5, 30]), [3, 1, 2, 2, 0, 3]);
+    assert_eq!(dense_rank(&[7, 7, 7]), [0, 0, 0]);
+}
+
+#[test]
+fn ranks_without_ties_are_the_same() {
+    let v = ["pear", "apple", "fig", "kiwi"];
+    assert_eq!(rank(&v), [3, 0, 1, 2]);
+    assert_eq!(dense_rank(&v), rank(&v));
+}
+
+#[test]
+fn empty_input_has_no_ranks() {
+    assert!(rank::<u8>(&[]).is_empty());
+    assert!(dense_rank::<u8>(&[]).is_empty());
+}
+
+#[test]
+fn sort_and_rank_present_and_absent() {
+    let mut v = vec![40, 10, 30, 20, 30];
+    assert_eq!(sort_and_rank(&mut v, &30), 2, "present: its first position");
+    assert_eq!(v, [10, 20, 30, 30, 40]);
+
+    assert_eq!(sort_and_rank(&mut v, &25), 2, "absent, between elements");
+    assert_eq!(sort_and_rank(&mut v, &0), 0, "below all");
+    assert_eq!(sort_and_rank(&mut v, &99), 5, "above all");
+    assert_eq!(sort_and_rank(&mut [], &1), 0);
+}
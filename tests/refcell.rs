@@ -0,0 +1,26 @@
+//! Sorting a vector behind a `RefCell`.
+#![cfg(feature = "alloc")]
+
+use std::cell::RefCell;
+
+use sort::*;
+
+#[test]
+fn sorts_through_the_cell() {
+    let cell = RefCell::new(vec![3, 1, 2]);
+    sort_refcell(&cell);
+    assert_eq!(*cell.borrow(), [1, 2, 3]);
+
+    // the borrow is dropped once sorted
+    cell.borrow_mut().push(0);
+    sort_refcell(&cell);
+    assert_eq!(*cell.borrow(), [0, 1, 2, 3]);
+}
+
+#[test]
+#[should_panic(expected = "already borrowed")]
+fn panics_on_an_active_borrow() {
+    let cell = RefCell::new(vec![3, 1, 2]);
+    let _borrowed = cell.borrow();
+    sort_refcell(&cell);
+}
@@ -0,0 +1,25 @@
+//! Sorting in decreasing order with `sort_reverse`, as by wrapping elements in
+//! `Reverse`, which `tests/properties.rs` sorts with every sort.
+#![cfg(feature = "std")]
+
+use std::cmp::Reverse;
+
+use sort::*;
+
+/// A scrambled input with repeats.
+fn input() -> Vec<u32> {
+    (0..200).map(|i| (i * 7919) % 37).collect()
+}
+
+#[test]
+fn sort_reverse_matches_sorting_reverse_wrapped() {
+    let mut v = input();
+    sort_reverse(&mut v);
+
+    let mut wrapped: Vec<Reverse<u32>> = input().into_iter().map(Reverse).collect();
+    wrapped.sort();
+    let unwrapped: Vec<u32> = wrapped.into_iter().map(|Reverse(x)| x).collect();
+
+    assert_eq!(v, unwrapped);
+    assert!(v.windows(2).all(|w| w[0] >= w[1]));
+}
@@ -0,0 +1,39 @@
+//! Shell sort on owned elements that are not `Copy`.
+
+use sort::*;
+
+/// Words in a scrambled order, with repeats, long enough for several gaps.
+fn words() -> Vec<String> {
+    (0..300)
+        .map(|i| format!("word{}", (i * 7919) % 97))
+        .collect()
+}
+
+#[test]
+fn sorts_strings() {
+    let mut v = words();
+    let mut expected = v.clone();
+    expected.sort();
+    shell_sort(&mut v);
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn sorts_strings_by_key_and_in_decreasing_order() {
+    let mut v = words();
+    shell_sort_by_key(&mut v, |s| s.len());
+    assert!(v.windows(2).all(|w| w[0].len() <= w[1].len()));
+
+    let mut v = words();
+    shell_sort_desc(&mut v);
+    assert!(v.windows(2).all(|w| w[0] >= w[1]));
+}
+
+#[test]
+fn sorts_vectors() {
+    let mut v: Vec<Vec<u8>> = (0..100u8).rev().map(|i| vec![i % 7, i]).collect();
+    let mut expected = v.clone();
+    expected.sort();
+    shell_sort(&mut v);
+    assert_eq!(v, expected);
+}
@@ -0,0 +1,109 @@
+//! Every sort on empty, one and two element inputs, where index arithmetic
+//! is most likely to underflow or run out of bounds.
+#![cfg(feature = "std")]
+
+use sort::*;
+
+/// All inputs of up to two elements, with and without equal elements.
+fn small_inputs() -> Vec<Vec<usize>> {
+    vec![vec![], vec![1], vec![1, 2], vec![2, 1], vec![2, 2]]
+}
+
+/// Expands to a test that sorts every small input with each given function.
+macro_rules! test_small {
+    ( $test:ident , $( $sort_fn:expr ),+ $( , )? ) => {
+        #[test]
+        fn $test() {
+            $(
+                for input in small_inputs() {
+                    let mut v = input.clone();
+                    $sort_fn(&mut v);
+                    let mut expected = input.clone();
+                    expected.sort();
+                    assert_eq!(v, expected, "{} failed on {:?}", stringify!($sort_fn), input);
+                }
+            )+
+        }
+    };
+}
+
+test_small!(
+    small_inputs_sorted,
+    gnome_sort,
+    bubble_sort,
+    cocktail_sort,
+    odd_even_sort,
+    bitonic_sort,
+    comb_sort,
+    selection_sort,
+    |v: &mut [usize]| selection_sort_opts(v, true),
+    pancake_sort,
+    insertion_sort,
+    shell_sort,
+    heap_sort,
+    smooth_sort,
+    heap_sort_topdown_build,
+    intro_sort,
+    sort,
+    quick_sort_median_of_3,
+    quick_sort_median_of_medians,
+    |v: &mut [usize]| heap_sort_partial(v, 2),
+    |v: &mut [usize]| partial_sort(v, 2),
+    |v: &mut [usize]| partial_sort_largest(v, 2),
+    merge_sort_top_down,
+    |v: &mut [usize]| merge_sort_with_buffer(v, &mut vec![7, 7, 7]),
+    merge_sort_top_down_insert,
+    merge_sort_top_down_adaptive,
+    merge_sort_copy,
+    merge_sort_bottom_up,
+    merge_sort_bottom_up_insert,
+    merge_sort_blocked,
+    merge_sort_in_place,
+    merge_sort_owned,
+    tim_sort,
+    |v: &mut [usize]| merge_sort_with_progress(v, |_| ()),
+    merge_sort_parallel_stable,
+    radix_sort,
+    native_sort,
+    native_unstable_sort,
+    sort_minimize_moves,
+    |v: &mut [usize]| ensure_sorted(v),
+    |v: &mut [usize]| sort_min_writes(v),
+    |v: &mut [usize]| sort_move_capped(v, usize::MAX),
+    |v: &mut [usize]| sort_and_rank(v, &1),
+    |v: &mut [usize]| pigeonhole_sort(v, |&x| x),
+    sort_interned,
+    |v: &mut [usize]| {
+        let mut w: Vec<u32> = v.iter().map(|&x| x as u32).collect();
+        counting_sort(&mut w, 2);
+        v.iter_mut().zip(w).for_each(|(x, y)| *x = y as usize);
+    },
+    |v: &mut [usize]| {
+        let mut timestamps: Vec<u64> = v.iter().map(|&x| x as u64).collect();
+        sort_timestamps(&mut timestamps);
+        v.iter_mut()
+            .zip(timestamps)
+            .for_each(|(x, t)| *x = t as usize);
+    },
+);
+
+#[cfg(feature = "fastrand")]
+test_small!(
+    small_inputs_sorted_random_pivot,
+    quick_sort,
+    quick_sort_3,
+    |v: &mut [usize]| quick_sort_seeded(v, 42),
+    |v: &mut [usize]| quick_sort_3_seeded(v, 42),
+    |v: &mut [usize]| quick_sort_sampled_median(v, 3),
+    quick_sort_hinted,
+    |v: &mut [usize]| quick_sort_depth_cutoff(v, 2),
+    |v: &mut [usize]| quick_sort_trace(v),
+    |v: &mut [usize]| quick_sort_by_key(v, |x| *x),
+    sample_sort,
+);
+
+#[cfg(feature = "parallel")]
+test_small!(small_inputs_sorted_parallel, par_merge_sort);
+
+#[cfg(feature = "rayon")]
+test_small!(small_inputs_sorted_rayon, par_quick_sort);
@@ -0,0 +1,69 @@
+//! Smoothsort on every distribution of the `generators` module, at every
+//! length where the Leonardo trees take a different shape, and beyond.
+#![cfg(feature = "testutil")]
+
+use sort::{generators::*, *};
+
+/// The inputs of length `n` to sort, seeded with `seed` when random.
+fn inputs(n: usize, seed: u64) -> Vec<Vec<usize>> {
+    vec![
+        random_sequence(n, seed),
+        increasing_sequence(n),
+        decreasing_sequence(n),
+        equal_sequence(n),
+        last_out_of_order(n),
+        first_out_of_order(n),
+        nearly_sorted_sequence(n, 3, seed),
+        few_unique_sequence(n, 3, seed),
+        pipe_organ_sequence(n),
+        sawtooth_sequence(n, 7),
+    ]
+}
+
+/// Sort each input of length `n` and check against the native sort.
+fn check(n: usize, seed: u64) {
+    for input in inputs(n, seed) {
+        let mut v = input.clone();
+        smooth_sort(&mut v);
+        let mut expected = input.clone();
+        expected.sort();
+        assert_eq!(v, expected, "failed on {input:?}");
+    }
+}
+
+#[test]
+fn short_inputs() {
+    for n in 0..200 {
+        check(n, n as u64);
+    }
+}
+
+#[test]
+fn leonardo_lengths() {
+    // a single tree, and one element either side of it
+    for n in [41, 67, 109, 177, 287, 465, 753, 1219, 1973] {
+        check(n - 1, 1);
+        check(n, 2);
+        check(n + 1, 3);
+    }
+}
+
+#[test]
+fn long_inputs() {
+    check(10_000, 42);
+}
+
+#[test]
+fn sorted_input_is_linear() {
+    let counts = Counts::new();
+    let mut v: Vec<_> = increasing_sequence(10_000)
+        .into_iter()
+        .map(|x| Counted::new(x, &counts))
+        .collect();
+    smooth_sort(&mut v);
+    assert!(
+        counts.comparisons() < 5 * v.len(),
+        "{} comparisons",
+        counts.comparisons()
+    );
+}
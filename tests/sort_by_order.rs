@@ -0,0 +1,60 @@
+//! Sorting by the position of each key in a given order, with unknown keys
+//! sorted last or reported.
+#![cfg(feature = "std")]
+
+use sort::*;
+
+const PRIORITIES: &[&str] = &["critical", "high", "medium", "low"];
+
+#[test]
+fn sorts_by_priority() {
+    let mut v = vec!["low", "critical", "medium", "high", "low", "critical"];
+    sort_by_order(&mut v, |x| *x, PRIORITIES, UnknownKeys::Error).unwrap();
+    assert_eq!(v, ["critical", "critical", "high", "medium", "low", "low"]);
+}
+
+#[test]
+fn is_stable() {
+    let mut v = vec![
+        ("low", 0),
+        ("high", 1),
+        ("low", 2),
+        ("high", 3),
+        ("medium", 4),
+    ];
+    sort_by_order(&mut v, |x| x.0, PRIORITIES, UnknownKeys::Error).unwrap();
+    assert_eq!(
+        v,
+        [
+            ("high", 1),
+            ("high", 3),
+            ("medium", 4),
+            ("low", 0),
+            ("low", 2)
+        ]
+    );
+}
+
+#[test]
+fn unknown_keys_last() {
+    let mut v = vec!["trivial", "low", "urgent", "critical"];
+    sort_by_order(&mut v, |x| *x, PRIORITIES, UnknownKeys::Last).unwrap();
+    assert_eq!(v, ["critical", "low", "trivial", "urgent"]);
+}
+
+#[test]
+fn unknown_keys_error() {
+    let mut v = vec!["low", "critical", "urgent", "trivial"];
+    let result = sort_by_order(&mut v, |x| *x, PRIORITIES, UnknownKeys::Error);
+    assert_eq!(result, Err(UnknownKey { index: 2 }));
+    assert_eq!(v, ["low", "critical", "urgent", "trivial"]);
+}
+
+#[test]
+fn empty_inputs() {
+    let mut v: Vec<&str> = vec![];
+    sort_by_order(&mut v, |x| *x, PRIORITIES, UnknownKeys::Error).unwrap();
+    let mut v = vec!["b", "a"];
+    sort_by_order(&mut v, |x| *x, &[], UnknownKeys::Last).unwrap();
+    assert_eq!(v, ["b", "a"]);
+}
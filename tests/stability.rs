@@ -0,0 +1,147 @@
+//! Stable sorts keep equal elements in their original order; unstable sorts
+//! only have to sort.
+#![cfg(feature = "std")]
+
+use std::cmp::Ordering;
+
+use sort::*;
+
+/// An element ordered only by `key`, remembering its original position.
+#[derive(Debug, Clone, Copy)]
+struct Record {
+    key: usize,
+    index: usize,
+}
+
+impl PartialEq for Record {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Record {}
+
+impl PartialOrd for Record {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Record {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// Records with few distinct keys, long enough to go past the insertion sort
+/// cutoffs, with each key spread all over the input.
+fn records() -> Vec<Record> {
+    (0..500)
+        .map(|index| Record {
+            key: (index * 7919) % 5,
+            index,
+        })
+        .collect()
+}
+
+/// Expands to a test that sorts the records with each given function and
+/// checks that they are in order by key, and, if `stable`, by original index
+/// within each key.
+macro_rules! test_stability {
+    ( $test:ident , $stable:expr , $( $sort_fn:expr ),+ $( , )? ) => {
+        #[test]
+        fn $test() {
+            $(
+                let mut v = records();
+                $sort_fn(&mut v);
+                for w in v.windows(2) {
+                    assert!(w[0].key <= w[1].key, "{} did not sort", stringify!($sort_fn));
+                    if $stable && w[0].key == w[1].key {
+                        assert!(
+                            w[0].index < w[1].index,
+                            "{} is not stable: {:?} before {:?}",
+                            stringify!($sort_fn),
+                            w[0],
+                            w[1],
+                        );
+                    }
+                }
+            )+
+        }
+    };
+}
+
+test_stability!(
+    stable_sorts_keep_order,
+    true,
+    gnome_sort,
+    bubble_sort,
+    cocktail_sort,
+    odd_even_sort,
+    insertion_sort,
+    |v: &mut [Record]| selection_sort_opts(v, true),
+    merge_sort_top_down,
+    |v: &mut [Record]| merge_sort_with_buffer(v, &mut vec![]),
+    merge_sort_copy,
+    merge_sort_top_down_insert,
+    merge_sort_top_down_adaptive,
+    merge_sort_bottom_up,
+    merge_sort_bottom_up_insert,
+    merge_sort_blocked,
+    |v: &mut [Record]| merge_sort_with_progress(v, |_| {}),
+    merge_sort_in_place,
+    merge_sort_owned,
+    merge_sort_parallel_stable,
+    tim_sort,
+    native_sort,
+    |v: &mut [Record]| insertion_sort_by_key(v, |r| r.key),
+    |v: &mut [Record]| cocktail_sort_by_key(v, |r| r.key),
+    |v: &mut [Record]| odd_even_sort_by_key(v, |r| r.key),
+    |v: &mut [Record]| merge_sort_in_place_by_key(v, |r| r.key),
+    |v: &mut [Record]| tim_sort_by_key(v, |r| r.key),
+    |v: &mut [Record]| merge_sort_bottom_up_by_key(v, |r| r.key),
+    |v: &mut [Record]| heap_sort_by_cached_key(v, |r| r.key),
+);
+
+test_stability!(
+    unstable_sorts_only_sort,
+    false,
+    comb_sort,
+    shell_sort,
+    selection_sort,
+    pancake_sort,
+    heap_sort,
+    smooth_sort,
+    intro_sort,
+    sort,
+    quick_sort_median_of_3,
+    quick_sort_median_of_medians,
+    native_unstable_sort,
+);
+
+#[cfg(feature = "fastrand")]
+test_stability!(
+    unstable_quick_sorts_only_sort,
+    false,
+    quick_sort,
+    quick_sort_3,
+    |v: &mut [Record]| quick_sort_seeded(v, 42),
+    |v: &mut [Record]| quick_sort_3_seeded(v, 42),
+);
+
+#[test]
+fn parallel_stable_on_many_records() {
+    // past the 16384 elements under which it sorts sequentially, so that the
+    // chunks sorted and merged on separate threads hold equal keys
+    let n = 100_000;
+    let mut v: Vec<Record> = (0..n)
+        .map(|index| Record {
+            key: (index * 7919) % 50,
+            index,
+        })
+        .collect();
+    merge_sort_parallel_stable(&mut v);
+    for w in v.windows(2) {
+        assert!(w[0].key < w[1].key || (w[0].key == w[1].key && w[0].index < w[1].index));
+    }
+}
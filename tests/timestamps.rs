@@ -0,0 +1,76 @@
+//! Sorting timestamps that arrive almost in order.
+#![cfg(feature = "alloc")]
+
+use std::{cell::Cell, cmp::Ordering};
+
+use sort::*;
+
+thread_local! {
+    /// Comparisons of [`Stamp`] on this thread.
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A `u64` timestamp counting its comparisons in [`COMPARISONS`]; `Copy`, so
+/// it cannot hold a reference to its counts as `Counted` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp(u64);
+
+impl PartialOrd for Stamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Stamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.set(COMPARISONS.get() + 1);
+        self.0.cmp(&other.0)
+    }
+}
+
+/// `n` increasing timestamps with the ones at `late` arriving `delay`
+/// positions after their turn.
+fn with_late_arrivals(n: u64, late: &[usize], delay: usize) -> Vec<u64> {
+    let mut v: Vec<u64> = (0..n).map(|t| t * 10).collect();
+    for &i in late {
+        v[i..=i + delay].rotate_left(1);
+    }
+    v
+}
+
+#[test]
+fn late_arrivals_are_sorted() {
+    let mut v = with_late_arrivals(1000, &[10, 200, 201, 650, 990], 4);
+    let mut expected = v.clone();
+    expected.sort();
+    sort_timestamps(&mut v);
+    assert_eq!(v, expected);
+
+    for mut v in [vec![], vec![5], vec![3, 1], (0..100).rev().collect()] {
+        let mut expected = v.clone();
+        expected.sort();
+        sort_timestamps(&mut v);
+        assert_eq!(v, expected);
+    }
+}
+
+#[test]
+fn few_late_arrivals_take_linear_work() {
+    let n = 10_000;
+    let input = with_late_arrivals(n as u64, &[100, 2000, 5000, 7000, 9000], 3);
+    let mut v: Vec<Stamp> = input.iter().map(|&t| Stamp(t)).collect();
+
+    COMPARISONS.set(0);
+    sort_timestamps(&mut v);
+    assert!(v.windows(2).all(|w| w[0].0 <= w[1].0));
+
+    // one comparison per element to find the late ones, a few to sort them,
+    // and one per element merged past from the back, from the earliest late
+    // one on, against about `n log2(n)` for a full sort
+    let comparisons = COMPARISONS.get();
+    assert!(comparisons < 2 * n, "{comparisons} comparisons");
+
+    COMPARISONS.set(0);
+    merge_sort_copy(&mut input.iter().map(|&t| Stamp(t)).collect::<Vec<_>>());
+    assert!(comparisons < COMPARISONS.get() / 3);
+}
@@ -0,0 +1,46 @@
+//! The `sort` entry point on two and three elements, where it uses sorting
+//! networks instead of the general algorithm.
+
+use sort::*;
+
+/// Every sequence of `len` elements out of `0..len`, so with every permutation
+/// of distinct elements and every pattern of equal ones.
+fn all_sequences(len: u32) -> Vec<Vec<u32>> {
+    (0..len.pow(len))
+        .map(|mut code| {
+            (0..len)
+                .map(|_| {
+                    let digit = code % len;
+                    code /= len;
+                    digit
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn sorts_every_sequence_of_two_and_three() {
+    for len in [2, 3] {
+        for input in all_sequences(len) {
+            let mut v = input.clone();
+            sort(&mut v);
+            let mut expected = input.clone();
+            expected.sort();
+            assert_eq!(v, expected, "failed on {input:?}");
+        }
+    }
+}
+
+#[test]
+fn optimal_comparisons() {
+    let counts = Counts::new();
+    for (len, max) in [(2, 1), (3, 3)] {
+        for input in all_sequences(len) {
+            let mut v: Vec<_> = input.iter().map(|&x| Counted::new(x, &counts)).collect();
+            counts.reset();
+            sort(&mut v);
+            assert!(counts.comparisons() <= max, "{input:?}");
+        }
+    }
+}